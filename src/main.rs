@@ -1,21 +1,27 @@
 mod modules;
 use modules::create_dataset::random_f64_matrix;
 use modules::dataset_struct::Dataset;
+use modules::error::MlError;
+use modules::inercia::{calcular_inercia, metodo_del_codo};
 use modules::kmeans::{
-    actualizar_centroides, asignar_a_clusters, iterar_hasta_optimo, kmeans_plus_plus,
-    map_centroids_to_labels, show_cluster_info, show_cluster_stats, show_sample_cluster,
+    actualizar_centroides, kmeans_plus_plus, map_centroids_to_labels, show_cluster_info,
+    show_cluster_stats, show_sample_cluster, KMeans,
 };
+use modules::silueta::{coeficientes_silueta, silueta_promedio};
+use modules::verbosidad::Verbosity;
 use modules::*;
 use rand::Rng;
 use std::collections::HashMap;
 use std::vec;
 
-fn main() {
+fn main() -> Result<(), MlError> {
     const NUM_SAMPLES: usize = 1000;
     const NUM_FEATURES: usize = 2;
     const MAX: f64 = 100.0;
     const MIN: f64 = 0.0;
-    const K: usize = 3; // Set to match the actual number of clusters used
+    const K_MIN: usize = 1;
+    const K_MAX: usize = 10;
+    const SEED: Option<u64> = Some(42); // None para resultados distintos en cada corrida
 
     // Feature names
     let feature_names = vec!["X".to_string(), "Y".to_string()];
@@ -31,18 +37,33 @@ fn main() {
         MAX,
         feature_names,
         cluster_labels.clone(),
-    );
+        SEED,
+    )?;
 
     let training_data = training_dataset.rows.clone();
 
+    // Elegir K con el metodo del codo en lugar de adivinarlo
+    let codo = metodo_del_codo(&training_data, K_MIN, K_MAX, 100, SEED)?;
+    for (k, inercia) in codo.ks.iter().zip(codo.inercias.iter()) {
+        println!("K = {}: inercia {:.4}", k, inercia);
+    }
+    let k = codo.k_optimo;
+    println!("K elegido por el metodo del codo: {}", k);
+
     // Run KMeans clustering
-    let final_centroids = iterar_hasta_optimo(100, training_data.clone(), K);
+    let mut kmeans = KMeans::new(k)
+        .with_max_iters(100)
+        .with_n_init(10)
+        .with_seed(SEED)
+        .with_verbosity(Verbosity::Verbose);
+    kmeans.fit(&training_data)?;
+    let final_centroids = kmeans.centroids()?.to_vec();
 
     // Create a mapping from centroid index to custom label
     let centroid_labels = map_centroids_to_labels(&final_centroids, &cluster_labels);
 
-    // Assign each data point to a cluster
-    let point_clusters = asignar_a_clusters(training_data.clone(), &final_centroids);
+    // Cluster assigned to each data point during fit
+    let point_clusters = kmeans.labels()?.to_vec();
 
     show_cluster_info(&final_centroids, &centroid_labels);
 
@@ -54,5 +75,38 @@ fn main() {
 
     show_cluster_stats(&cluster_counts, &centroid_labels, &point_clusters);
 
+    // Inercia (WCSS) para comparar corridas de KMeans
+    match calcular_inercia(&training_data, &final_centroids, &point_clusters) {
+        Ok(inercia) => {
+            println!("\nInercia total: {:.4}", inercia.total);
+            for (i, valor) in inercia.por_cluster.iter().enumerate() {
+                println!("Cluster {}: inercia {:.4}", i, valor);
+            }
+        }
+        Err(e) => eprintln!("Error calculando inercia: {}", e),
+    }
+
+    match silueta_promedio(&training_data, &point_clusters) {
+        Ok(silueta) => println!("Silueta promedio: {:.4}", silueta),
+        Err(e) => eprintln!("Error calculando silueta: {}", e),
+    }
+
+    // Grafica de silueta por cluster
+    match coeficientes_silueta(&training_data, &point_clusters) {
+        Ok(coeficientes) => {
+            if let Err(e) = graphs::plot_silueta(
+                &coeficientes,
+                &point_clusters,
+                "silueta.png",
+                Some("Silueta de KMeans"),
+            ) {
+                eprintln!("Error graficando silueta: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Error calculando silueta: {}", e),
+    }
+
     show_sample_cluster(&training_data, &centroid_labels, &point_clusters,20);
+
+    Ok(())
 }
@@ -1,6 +1,20 @@
+#[allow(non_snake_case)]
+pub mod ID3;
 pub mod create_dataset;
+pub mod csv_manipulation;
+pub mod dataset_manipulation;
 pub mod dataset_struct;
 pub mod distancias;
+pub mod error;
 pub mod graphs;
+pub mod inercia;
+pub mod kd_tree;
 pub mod kmeans;
+pub mod kmeans_minibatch;
+pub mod kmedoids;
 pub mod knn_ia;
+pub mod knn_regresion;
+pub mod metricas;
+pub mod silueta;
+pub mod validacion_cruzada;
+pub mod verbosidad;
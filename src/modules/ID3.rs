@@ -1,7 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
-use crate::Modules::create_dataset::DatosCualitativos;
+use super::create_dataset::DatosCualitativos;
+use super::error::MlError;
 
 // Node structure for the decision tree
 #[derive(Debug, Clone)]
@@ -45,11 +46,11 @@ pub fn calculate_information_gain(
     data: &[DatosCualitativos],
     target_attr: &str,
     attribute: &str,
-) -> f64 {
+) -> Result<f64, MlError> {
     // Extract the target classes
     let target_values: Vec<String> = data.iter()
         .map(|d| get_attribute_value(d, target_attr))
-        .collect();
+        .collect::<Result<_, MlError>>()?;
     
     // Calculate base entropy
     let base_entropy = calculate_entropy(&target_values);
@@ -57,9 +58,9 @@ pub fn calculate_information_gain(
     // Group data by attribute values
     let mut attribute_groups: HashMap<String, Vec<String>> = HashMap::new();
     for item in data {
-        let attr_value = get_attribute_value(item, attribute);
-        let target_value = get_attribute_value(item, target_attr);
-        attribute_groups.entry(attr_value).or_insert_with(Vec::new).push(target_value);
+        let attr_value = get_attribute_value(item, attribute)?;
+        let target_value = get_attribute_value(item, target_attr)?;
+        attribute_groups.entry(attr_value).or_default().push(target_value);
     }
     
     // Calculate weighted entropy sum
@@ -72,19 +73,19 @@ pub fn calculate_information_gain(
         .sum::<f64>();
     
     // Information gain = base entropy - weighted entropy
-    base_entropy - weighted_entropy
+    Ok(base_entropy - weighted_entropy)
 }
 
 // Helper function to get attribute value from DatosCualitativos
-fn get_attribute_value(item: &DatosCualitativos, attribute: &str) -> String {
+fn get_attribute_value(item: &DatosCualitativos, attribute: &str) -> Result<String, MlError> {
     match attribute {
-        "negocio" => item.negocio.clone(),
-        "calificacion" => item.calificacion.clone(),
-        "estilo" => item.estilo.clone(),
-        "recomendado" => item.recomendado.clone(),
-        "fecha_resena" => item.fecha_resena.clone(),
-        "tipo_comida" => item.tipo_comida.clone(),
-        _ => panic!("Attribute '{}' not found in DatosCualitativos", attribute),
+        "negocio" => Ok(item.negocio.clone()),
+        "calificacion" => Ok(item.calificacion.clone()),
+        "estilo" => Ok(item.estilo.clone()),
+        "recomendado" => Ok(item.recomendado.clone()),
+        "fecha_resena" => Ok(item.fecha_resena.clone()),
+        "tipo_comida" => Ok(item.tipo_comida.clone()),
+        _ => Err(MlError::UnknownAttribute(attribute.to_string())),
     }
 }
 
@@ -94,19 +95,19 @@ pub fn build_decision_tree(
     attributes: &[&str],
     target_attr: &str,
     min_samples: usize,
-) -> DecisionNode {
+) -> Result<DecisionNode, MlError> {
     // If dataset is empty, return default leaf
     if data.is_empty() {
-        return DecisionNode::Leaf {
+        return Ok(DecisionNode::Leaf {
             class: "unknown".to_string(),
             count: 0,
-        };
+        });
     }
     
     // Extract target classes and their counts
     let mut class_counts: HashMap<String, usize> = HashMap::new();
     for item in data {
-        let class = get_attribute_value(item, target_attr);
+        let class = get_attribute_value(item, target_attr)?;
         *class_counts.entry(class).or_insert(0) += 1;
     }
     
@@ -118,21 +119,21 @@ pub fn build_decision_tree(
     
     // If all examples have the same class or remaining data is too small
     if class_counts.len() == 1 || data.len() <= min_samples || attributes.is_empty() {
-        return DecisionNode::Leaf {
+        return Ok(DecisionNode::Leaf {
             class: majority_class,
             count: majority_count,
-        };
+        });
     }
     
     // Find best attribute to split on using information gain
-    let best_attribute = attributes.iter()
-        .max_by(|&a, &b| {
-            let gain_a = calculate_information_gain(data, target_attr, a);
-            let gain_b = calculate_information_gain(data, target_attr, b);
-            gain_a.partial_cmp(&gain_b).unwrap()
-        })
-        .copied()
-        .unwrap();
+    let mut gains = Vec::with_capacity(attributes.len());
+    for &attribute in attributes {
+        gains.push((attribute, calculate_information_gain(data, target_attr, attribute)?));
+    }
+    let best_attribute = gains.iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(attribute, _)| *attribute)
+        .ok_or(MlError::EmptyDataset)?;
     
     // Create remaining attributes list (without the best one)
     let remaining_attributes: Vec<&str> = attributes.iter()
@@ -143,33 +144,33 @@ pub fn build_decision_tree(
     // Group data by best attribute values
     let mut value_groups: HashMap<String, Vec<DatosCualitativos>> = HashMap::new();
     for item in data {
-        let value = get_attribute_value(item, best_attribute);
-        value_groups.entry(value).or_insert_with(Vec::new).push(item.clone());
+        let value = get_attribute_value(item, best_attribute)?;
+        value_groups.entry(value).or_default().push(item.clone());
     }
     
     // Create branches for each value
     let mut branches = HashMap::new();
     for (value, group) in value_groups {
-        let subtree = build_decision_tree(&group, &remaining_attributes, target_attr, min_samples);
+        let subtree = build_decision_tree(&group, &remaining_attributes, target_attr, min_samples)?;
         branches.insert(value, Box::new(subtree));
     }
     
-    DecisionNode::Internal {
+    Ok(DecisionNode::Internal {
         attribute: best_attribute.to_string(),
         branches,
         default_class: majority_class,
-    }
+    })
 }
 
 // Predict class for a single data point using the decision tree
-pub fn predict(tree: &DecisionNode, item: &DatosCualitativos) -> String {
+pub fn predict(tree: &DecisionNode, item: &DatosCualitativos) -> Result<String, MlError> {
     match tree {
-        DecisionNode::Leaf { class, .. } => class.clone(),
+        DecisionNode::Leaf { class, .. } => Ok(class.clone()),
         DecisionNode::Internal { attribute, branches, default_class } => {
-            let value = get_attribute_value(item, attribute);
+            let value = get_attribute_value(item, attribute)?;
             match branches.get(&value) {
                 Some(subtree) => predict(subtree, item),
-                None => default_class.clone(), // Use default if value not in training data
+                None => Ok(default_class.clone()), // Use default if value not in training data
             }
         }
     }
@@ -1,6 +1,7 @@
 // Import the necessary items from nalgebra
 use nalgebra::DMatrix;
-use rand::{distributions::Slice, thread_rng, Rng};
+use rand::{distributions::Slice, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::Serialize;
 use smartcore::linalg::basic::matrix::DenseMatrix;
 
@@ -14,21 +15,33 @@ pub struct DatosCualitativos {
     pub tipo_comida: String,
 }
 
+/// Crea el generador aleatorio usado por todas las funciones estocasticas
+///
+/// Con `Some(seed)` los resultados son reproducibles entre corridas,
+/// con `None` se inicializa desde la entropia del sistema
+pub fn crear_rng(random_seed: Option<u64>) -> ChaCha8Rng {
+    match random_seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
 pub fn create_matrix_numerical(
     rows: usize,
     columnas: usize,
     valor_minimo: f64,
     valor_maximo: f64,
+    random_seed: Option<u64>,
 ) -> DMatrix<f64> {
-    let mut rng = rand::thread_rng();
+    let mut rng = crear_rng(random_seed);
     let matrix: DMatrix<f64> = DMatrix::<f64>::from_fn(rows, columnas, |_, _| {
         rng.gen_range(valor_minimo..valor_maximo)
     });
     matrix
 }
 
-pub fn create_qualitative_matrix(n: usize) -> Vec<DatosCualitativos> {
-    let mut rng = thread_rng();
+pub fn create_qualitative_matrix(n: usize, random_seed: Option<u64>) -> Vec<DatosCualitativos> {
+    let mut rng = crear_rng(random_seed);
     let negocios = [
         "Guero Isaac",
         "Muuz",
@@ -65,8 +78,14 @@ pub fn create_qualitative_matrix(n: usize) -> Vec<DatosCualitativos> {
         .collect()
 }
 
-pub fn random_dense_matrix(rows: usize, cols: usize, min: f64, max: f64) -> DenseMatrix<f64> {
-    let mut rng = rand::thread_rng();
+pub fn random_dense_matrix(
+    rows: usize,
+    cols: usize,
+    min: f64,
+    max: f64,
+    random_seed: Option<u64>,
+) -> DenseMatrix<f64> {
+    let mut rng = crear_rng(random_seed);
 
     let data: Vec<Vec<f64>> = (0..rows)
         .map(|_| (0..cols).map(|_| rng.gen_range(min..max)).collect())
@@ -82,10 +101,15 @@ pub fn random_dense_matrix(rows: usize, cols: usize, min: f64, max: f64) -> Dens
 /// # Params
 /// - 'n': tamaño de la lista
 /// - min,max: rango de valores generados en la lista
+/// - random_seed: semilla opcional para resultados reproducibles
 ///
 /// #Regresa (Return) -> Vec<f64>
-pub fn create_lista(n: usize, min: f64, max: f64) -> Vec<f64> {
-    let mut rng = rand::thread_rng();
+pub fn create_lista(n: usize, min: f64, max: f64, random_seed: Option<u64>) -> Vec<f64> {
+    let mut rng = crear_rng(random_seed);
+    lista_con_rng(n, min, max, &mut rng)
+}
+
+pub(crate) fn lista_con_rng<R: Rng>(n: usize, min: f64, max: f64, rng: &mut R) -> Vec<f64> {
     (0..n).map(|_| rng.gen_range(min..max)).collect()
 }
 
@@ -94,10 +118,38 @@ pub fn create_lista(n: usize, min: f64, max: f64) -> Vec<f64> {
 /// -e_lists:usize = elementos por cada lista o eje x
 /// -n_listas:usize = numero de listas o eje Y
 /// -min,max = rango de valores creados
+/// -random_seed = semilla opcional para resultados reproducibles
 ///
 /// #Regresa(return) -> Vec<Vec<f64>>
-pub fn random_f64_matrix(e_lista: usize, n_listas: usize, min: f64, max: f64) -> Vec<Vec<f64>> {
+pub fn random_f64_matrix(
+    e_lista: usize,
+    n_listas: usize,
+    min: f64,
+    max: f64,
+    random_seed: Option<u64>,
+) -> Vec<Vec<f64>> {
+    // Un solo generador para toda la matriz, asi las filas no se repiten
+    let mut rng = crear_rng(random_seed);
     (0..n_listas)
-        .map(|_| create_lista(e_lista, min, max))
+        .map(|_| lista_con_rng(e_lista, min, max, &mut rng))
+        .collect()
+}
+
+/// Filas reproducibles para las pruebas de KNN, KD-tree y K-means
+///
+/// Con `empates` las coordenadas son los enteros 0, 1 y 2: hay puntos duplicados y
+/// distancias empatadas que ejercitan el desempate por indice. Sin `empates` son
+/// reales uniformes en [-50, 50).
+#[cfg(test)]
+pub(crate) fn filas_de_prueba(n: usize, dim: usize, empates: bool, seed: u64) -> Vec<Vec<f64>> {
+    let mut rng = crear_rng(Some(seed));
+    (0..n)
+        .map(|_| {
+            if empates {
+                (0..dim).map(|_| rng.gen_range(0..3) as f64).collect()
+            } else {
+                lista_con_rng(dim, -50.0, 50.0, &mut rng)
+            }
+        })
         .collect()
 }
@@ -1,9 +1,9 @@
-use std::string;
-
-use csv::Writer;
+use csv::{Reader, Writer};
 use nalgebra::DMatrix;
 
 use super::create_dataset::DatosCualitativos;
+use super::dataset_struct::Dataset;
+use super::error::MlError;
 
 
 
@@ -34,4 +34,265 @@ pub fn write_vec_tocsv(filename: &str, data: &[DatosCualitativos]) -> Result<(),
 
     Ok(())
 
-}
\ No newline at end of file
+}
+
+impl Dataset {
+    /// Carga un dataset desde un CSV con encabezado
+    ///
+    /// # Parametros
+    /// - `path`: Ruta al archivo CSV
+    /// - `label_column`: Nombre de la columna con la clase de cada muestra
+    ///
+    /// Todas las columnas excepto `label_column` deben ser numericas y se
+    /// guardan en `rows`; sus nombres del encabezado llenan `feature_names`.
+    /// La columna de etiquetas se guarda tal cual en `class_labels`.
+    ///
+    /// # Returns
+    /// - Err(MlError::UnknownAttribute) si `label_column` no esta en el encabezado
+    /// - Err(MlError::ParseError) con linea, columna y valor de la primera celda no numerica
+    /// - Err(MlError::Csv) si el archivo no se puede leer o una fila tiene otro numero de columnas
+    pub fn from_csv(path: &str, label_column: &str) -> Result<Dataset, MlError> {
+        let mut rdr = Reader::from_path(path)?;
+        let headers = rdr.headers()?.clone();
+
+        let label_idx = headers
+            .iter()
+            .position(|h| h.trim() == label_column)
+            .ok_or_else(|| MlError::UnknownAttribute(label_column.to_string()))?;
+
+        let feature_names: Vec<String> = headers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != label_idx)
+            .map(|(_, h)| h.trim().to_string())
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut class_labels = Vec::new();
+
+        for result in rdr.records() {
+            let record = result?;
+            let line = record.position().map_or(0, |p| p.line());
+
+            let mut row = Vec::with_capacity(feature_names.len());
+            for (i, value) in record.iter().enumerate() {
+                if i == label_idx {
+                    class_labels.push(value.trim().to_string());
+                    continue;
+                }
+                let parsed = value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| MlError::ParseError {
+                        line,
+                        column: headers[i].trim().to_string(),
+                        value: value.to_string(),
+                    })?;
+                row.push(parsed);
+            }
+            rows.push(row);
+        }
+
+        Ok(Dataset::new(rows, feature_names, class_labels))
+    }
+
+    /// Escribe el dataset a un CSV con encabezado
+    ///
+    /// # Parametros
+    /// - `path`: Ruta del archivo a crear
+    /// - `label_column`: Nombre de la columna para `class_labels`, se omite si el dataset no tiene etiquetas
+    /// - `extra_columns`: Columnas adicionales (nombre, valores) unidas a cada fila,
+    ///   p. ej. clusters de `asignar_a_clusters` o predicciones de `evaluate_knn`
+    ///
+    /// Si `feature_names` esta vacio las columnas se llaman `x0`, `x1`, ...
+    ///
+    /// # Returns
+    /// - Err(MlError::InvalidInput) si las etiquetas o alguna columna extra no tienen un valor por fila,
+    ///   o si alguna fila no tiene un valor por feature
+    /// - Err(MlError::Csv) si el archivo no se puede escribir
+    pub fn to_csv(
+        &self,
+        path: &str,
+        label_column: &str,
+        extra_columns: &[(&str, Vec<String>)],
+    ) -> Result<(), MlError> {
+        let n = self.rows.len();
+        let with_labels = !self.class_labels.is_empty();
+
+        if with_labels && self.class_labels.len() != n {
+            return Err(MlError::InvalidInput(format!(
+                "Dataset tiene {} muestras pero {} class labels",
+                n,
+                self.class_labels.len()
+            )));
+        }
+        for (name, values) in extra_columns {
+            if values.len() != n {
+                return Err(MlError::InvalidInput(format!(
+                    "Columna '{}' tiene {} valores pero el dataset tiene {} muestras",
+                    name,
+                    values.len(),
+                    n
+                )));
+            }
+        }
+
+        // Encabezado de features, generado si el dataset no tiene nombres
+        let feature_names: Vec<String> = if self.feature_names.is_empty() {
+            let dim = self.rows.first().map_or(0, |r| r.len());
+            (0..dim).map(|i| format!("x{}", i)).collect()
+        } else {
+            self.feature_names.clone()
+        };
+        // Validar antes de crear el archivo, asi un error no deja un CSV a medias
+        if let Some((i, row)) = self
+            .rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != feature_names.len())
+        {
+            return Err(MlError::InvalidInput(format!(
+                "Fila {} tiene {} valores pero hay {} nombres de features",
+                i,
+                row.len(),
+                feature_names.len()
+            )));
+        }
+
+        let mut wtr = Writer::from_path(path)?;
+
+        let mut header: Vec<&str> = feature_names.iter().map(|s| s.as_str()).collect();
+        if with_labels {
+            header.push(label_column);
+        }
+        header.extend(extra_columns.iter().map(|(name, _)| *name));
+        wtr.write_record(&header)?;
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut record: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            if with_labels {
+                record.push(self.class_labels[i].clone());
+            }
+            for (_, values) in extra_columns {
+                record.push(values[i].clone());
+            }
+            wtr.write_record(&record)?;
+        }
+
+        wtr.flush().map_err(|e| MlError::Csv(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // Archivo unico por prueba y por proceso dentro del directorio temporal
+    fn ruta_temporal(nombre: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ai_ml_{}_{}.csv", std::process::id(), nombre))
+    }
+
+    #[test]
+    fn to_csv_sin_nombres_genera_encabezado() {
+        let ruta = ruta_temporal("sin_nombres");
+        let dataset = Dataset::new(
+            vec![vec![1.0, 2.5], vec![3.0, 4.0]],
+            Vec::new(),
+            vec!["X".to_string(), "Y".to_string()],
+        );
+
+        dataset
+            .to_csv(ruta.to_str().unwrap(), "label", &[])
+            .unwrap();
+
+        let contenido = fs::read_to_string(&ruta).unwrap();
+        fs::remove_file(&ruta).unwrap();
+        assert_eq!(contenido, "x0,x1,label\n1,2.5,X\n3,4,Y\n");
+    }
+
+    #[test]
+    fn to_csv_con_filas_de_otro_ancho_no_crea_el_archivo() {
+        let ruta = ruta_temporal("ancho_distinto");
+        let dataset = Dataset::new(
+            vec![vec![1.0, 2.5], vec![3.0]],
+            vec!["a".to_string(), "b".to_string()],
+            Vec::new(),
+        );
+
+        assert!(matches!(
+            dataset.to_csv(ruta.to_str().unwrap(), "label", &[]),
+            Err(MlError::InvalidInput(_))
+        ));
+        assert!(!ruta.exists());
+    }
+
+    #[test]
+    fn from_csv_con_etiqueta_en_medio() {
+        let ruta = ruta_temporal("etiqueta_en_medio");
+        fs::write(&ruta, "alto, clase ,ancho\n1.5,A,2\n-3,B, 4.25\n").unwrap();
+
+        let dataset = Dataset::from_csv(ruta.to_str().unwrap(), "clase").unwrap();
+        fs::remove_file(&ruta).unwrap();
+
+        assert_eq!(dataset.feature_names, vec!["alto", "ancho"]);
+        assert_eq!(dataset.rows, vec![vec![1.5, 2.0], vec![-3.0, 4.25]]);
+        assert_eq!(dataset.class_labels, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn from_csv_reporta_celda_no_numerica() {
+        let ruta = ruta_temporal("celda_no_numerica");
+        fs::write(&ruta, "a,label,b\n1,X,2\n3,Y,oops\n").unwrap();
+
+        let resultado = Dataset::from_csv(ruta.to_str().unwrap(), "label");
+        fs::remove_file(&ruta).unwrap();
+
+        // La linea 1 es el encabezado
+        assert_eq!(
+            resultado.unwrap_err(),
+            MlError::ParseError {
+                line: 3,
+                column: "b".to_string(),
+                value: "oops".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn from_csv_sin_columna_de_etiquetas() {
+        let ruta = ruta_temporal("sin_etiquetas");
+        fs::write(&ruta, "a,b\n1,2\n").unwrap();
+
+        let resultado = Dataset::from_csv(ruta.to_str().unwrap(), "label");
+        fs::remove_file(&ruta).unwrap();
+
+        assert_eq!(
+            resultado.unwrap_err(),
+            MlError::UnknownAttribute("label".to_string())
+        );
+    }
+
+    #[test]
+    fn from_csv_lee_lo_que_escribe_to_csv() {
+        let ruta = ruta_temporal("ida_y_vuelta");
+        let original = Dataset::new(
+            vec![vec![0.1, -2.0, 1e-7], vec![3.5, 4.0, 123456.789]],
+            vec!["x".to_string(), "y".to_string(), "z".to_string()],
+            vec!["rojo".to_string(), "azul".to_string()],
+        );
+
+        original
+            .to_csv(ruta.to_str().unwrap(), "color", &[])
+            .unwrap();
+        let leido = Dataset::from_csv(ruta.to_str().unwrap(), "color").unwrap();
+        fs::remove_file(&ruta).unwrap();
+
+        assert_eq!(leido.feature_names, original.feature_names);
+        assert_eq!(leido.rows, original.rows);
+        assert_eq!(leido.class_labels, original.class_labels);
+    }
+}
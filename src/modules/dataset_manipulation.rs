@@ -1,18 +1,18 @@
+use std::collections::BTreeMap;
+
 use rand::prelude::*;
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
-use std::fmt::Debug;
 
-use super::create_dataset::DatosCualitativos;
+use super::create_dataset::{crear_rng, DatosCualitativos};
+use super::dataset_struct::Dataset;
+use super::error::MlError;
 
 pub fn split_qualitative_dataset(
     data: Vec<DatosCualitativos>,
     test_size: f64,
     random_seed: Option<u64>,
-) -> (Vec<DatosCualitativos>,Vec<DatosCualitativos>){
-
-    if test_size < 0.0 || test_size > 1.0{
-        panic!("Test size debe ser entre 0.0 y 1.0");
+) -> Result<(Vec<DatosCualitativos>, Vec<DatosCualitativos>), MlError> {
+    if !(0.0..=1.0).contains(&test_size) {
+        return Err(MlError::InvalidTestSize(test_size));
     }
 
     //Creamos una copia mutable de los datos
@@ -20,10 +20,7 @@ pub fn split_qualitative_dataset(
 
     //Setup rng seed
 
-    let mut rng = match random_seed{
-        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
-        None => ChaCha8Rng::from_entropy(),
-    };
+    let mut rng = crear_rng(random_seed);
 
     //shuffle datos
     data_copy.shuffle(&mut rng);
@@ -36,6 +33,167 @@ pub fn split_qualitative_dataset(
     let train_data = data_copy[..split_idx].to_vec();
     let test_data = data_copy[split_idx..].to_vec();
 
-    (train_data,test_data)
+    Ok((train_data, test_data))
+}
+
+impl Dataset {
+    /// Divide el dataset en (training, testing) manteniendo `rows` y `class_labels` alineados
+    ///
+    /// # Parametros
+    /// - `test_size`: Proporcion de muestras para testing, entre 0.0 y 1.0
+    /// - `random_seed`: Semilla opcional para que el split sea reproducible
+    /// - `stratify`: Si es `true`, cada clase se divide por separado para que
+    ///   training y testing tengan la misma proporcion de clases
+    ///
+    /// # Returns
+    /// - Err(MlError::InvalidTestSize) si `test_size` esta fuera de [0.0, 1.0]
+    /// - Err(MlError::InvalidInput) si las etiquetas no coinciden con las filas,
+    ///   o si se pide `stratify` sin etiquetas
+    pub fn train_test_split(
+        &self,
+        test_size: f64,
+        random_seed: Option<u64>,
+        stratify: bool,
+    ) -> Result<(Dataset, Dataset), MlError> {
+        let mut rng = crear_rng(random_seed);
+        self.train_test_split_con_rng(test_size, stratify, &mut rng)
+    }
+
+    pub(crate) fn train_test_split_con_rng<R: Rng>(
+        &self,
+        test_size: f64,
+        stratify: bool,
+        rng: &mut R,
+    ) -> Result<(Dataset, Dataset), MlError> {
+        if !(0.0..=1.0).contains(&test_size) {
+            return Err(MlError::InvalidTestSize(test_size));
+        }
+        if !self.class_labels.is_empty() && self.class_labels.len() != self.rows.len() {
+            return Err(MlError::InvalidInput(format!(
+                "Dataset tiene {} muestras pero {} class labels",
+                self.rows.len(),
+                self.class_labels.len()
+            )));
+        }
+        if stratify && self.class_labels.is_empty() {
+            return Err(MlError::InvalidInput(
+                "No se puede estratificar un dataset sin class labels".to_string(),
+            ));
+        }
+
+        let mut train_idx = Vec::new();
+        let mut test_idx = Vec::new();
+
+        if stratify {
+            // Agrupar indices por clase, BTreeMap para un orden determinista
+            let mut por_clase: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+            for (i, label) in self.class_labels.iter().enumerate() {
+                por_clase.entry(label.as_str()).or_default().push(i);
+            }
+
+            // El total de testing se calcula una vez y se reparte entre las clases
+            // por residuo mayor, asi el redondeo por clase no se acumula en testing
+            let n_test = (self.rows.len() as f64 * test_size).round() as usize;
+            let tamanos: Vec<usize> = por_clase.values().map(|indices| indices.len()).collect();
+            let por_clase_test = repartir_proporcional(&tamanos, n_test);
+
+            for (indices, n_clase) in por_clase.values_mut().zip(por_clase_test) {
+                indices.shuffle(rng);
+                let split_idx = indices.len() - n_clase;
+                train_idx.extend_from_slice(&indices[..split_idx]);
+                test_idx.extend_from_slice(&indices[split_idx..]);
+            }
+
+            // Mezclar para no dejar las clases en bloques
+            train_idx.shuffle(rng);
+            test_idx.shuffle(rng);
+        } else {
+            let mut indices: Vec<usize> = (0..self.rows.len()).collect();
+            indices.shuffle(rng);
+            let split_idx = (indices.len() as f64 * (1.0 - test_size)) as usize;
+            test_idx = indices.split_off(split_idx);
+            train_idx = indices;
+        }
+
+        Ok((self.subset(&train_idx), self.subset(&test_idx)))
+    }
+}
+
+/// Reparte `total` entre grupos de los tamaños dados, proporcional a cada tamaño
+///
+/// Cada grupo recibe la parte entera de su cuota y las unidades que faltan van a
+/// los grupos con mayor residuo (el primero gana empates). Ningun grupo recibe
+/// mas que su tamaño si `total` no supera la suma de tamaños.
+fn repartir_proporcional(tamanos: &[usize], total: usize) -> Vec<usize> {
+    let n: usize = tamanos.iter().sum();
+    if n == 0 {
+        return vec![0; tamanos.len()];
+    }
+
+    let cuotas: Vec<f64> = tamanos
+        .iter()
+        .map(|&tamano| tamano as f64 * total as f64 / n as f64)
+        .collect();
+    let mut asignados: Vec<usize> = cuotas.iter().map(|c| c.floor() as usize).collect();
+    let residuos: Vec<f64> = cuotas.iter().map(|c| c - c.floor()).collect();
+
+    // sort_by es estable: con residuos iguales queda primero el grupo anterior
+    let mut por_residuo: Vec<usize> = (0..tamanos.len()).collect();
+    por_residuo.sort_by(|&a, &b| residuos[b].total_cmp(&residuos[a]));
+
+    let faltantes = total.saturating_sub(asignados.iter().sum());
+    for &g in por_residuo.iter().take(faltantes) {
+        asignados[g] += 1;
+    }
+
+    asignados
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset_por_clases(tamanos: &[usize]) -> Dataset {
+        let labels: Vec<String> = tamanos
+            .iter()
+            .enumerate()
+            .flat_map(|(c, &n)| std::iter::repeat_n(format!("C{}", c), n))
+            .collect();
+        let rows = (0..labels.len()).map(|i| vec![i as f64]).collect();
+        Dataset::new(rows, vec!["x".to_string()], labels)
+    }
+
+    fn contar(dataset: &Dataset, clase: &str) -> usize {
+        dataset.class_labels.iter().filter(|l| *l == clase).count()
+    }
+
+    #[test]
+    fn split_estratificado_respeta_test_size() {
+        // 8 A / 2 B con 0.2: redondear por clase daria 3 muestras de testing
+        let dataset = dataset_por_clases(&[8, 2]);
+        let (train, test) = dataset.train_test_split(0.2, Some(1), true).unwrap();
+        assert_eq!(test.rows.len(), 2);
+        assert_eq!(train.rows.len(), 8);
+        assert_eq!(contar(&test, "C0"), 2);
+        assert_eq!(contar(&test, "C1"), 0);
 
-}
\ No newline at end of file
+        // Muchas clases chicas: el total sigue siendo round(n * test_size)
+        let dataset = dataset_por_clases(&[3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 2, 1]);
+        for test_size in [0.1, 0.25, 0.3, 0.5, 0.8] {
+            let (train, test) = dataset.train_test_split(test_size, Some(7), true).unwrap();
+            let esperado = (dataset.rows.len() as f64 * test_size).round() as usize;
+            assert_eq!(test.rows.len(), esperado, "test_size {}", test_size);
+            assert_eq!(train.rows.len() + test.rows.len(), dataset.rows.len());
+        }
+    }
+
+    #[test]
+    fn repartir_proporcional_por_residuo_mayor() {
+        assert_eq!(repartir_proporcional(&[8, 2], 2), vec![2, 0]);
+        assert_eq!(repartir_proporcional(&[5, 3, 2], 5), vec![3, 1, 1]);
+        // Empate de residuos: gana el primer grupo
+        assert_eq!(repartir_proporcional(&[1, 1, 1], 1), vec![1, 0, 0]);
+        assert_eq!(repartir_proporcional(&[4, 0, 6], 10), vec![4, 0, 6]);
+        assert_eq!(repartir_proporcional(&[4, 6], 0), vec![0, 0]);
+    }
+}
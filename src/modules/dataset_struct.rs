@@ -1,5 +1,9 @@
 use rand::Rng;
 
+use super::create_dataset::{crear_rng, lista_con_rng};
+use super::error::MlError;
+
+#[derive(Debug, Clone)]
 pub struct Dataset {
     pub rows: Vec<Vec<f64>>,
     pub class_labels: Vec<String>,
@@ -35,17 +39,47 @@ impl Dataset {
         (self.rows.len(), self.rows[0].len())
     }
 
+    /// Clases distintas de `class_labels`, en orden alfabetico
+    pub fn unique_labels(&self) -> Vec<String> {
+        let mut labels = self.class_labels.clone();
+        labels.sort();
+        labels.dedup();
+        labels
+    }
+
+    /// Crea un nuevo dataset con las filas indicadas por `indices`, en ese orden
+    ///
+    /// Mantiene `rows` y `class_labels` alineados; si el dataset no tiene
+    /// etiquetas el resultado tampoco las tiene
+    pub fn subset(&self, indices: &[usize]) -> Dataset {
+        let rows = indices.iter().map(|&i| self.rows[i].clone()).collect();
+        let class_labels = if self.class_labels.is_empty() {
+            Vec::new()
+        } else {
+            indices
+                .iter()
+                .map(|&i| self.class_labels[i].clone())
+                .collect()
+        };
+        Dataset::new(rows, self.feature_names.clone(), class_labels)
+    }
+
     /// Inicializa los datasets de testing y training
     ///
+    /// Genera `2 * num_samples` muestras aleatorias y las divide a la mitad con
+    /// `train_test_split`, asi ambos datasets vienen de la misma distribucion.
+    ///
     /// # Parametros
     /// - `num_features`: Numero de clases por muestra
-    /// - `num_samples`: Numero de (filas) a generar EJE X
+    /// - `num_samples`: Numero de (filas) a generar EJE X para cada dataset
     /// - `min` and `max`: Rango de posibles valores a generar
     /// - `feature_names`: Nombres de las clases
     /// - `possible_classes`: Etiquetas o categorias posibles para las clases
+    /// - `random_seed`: Semilla opcional, con `Some` los datasets son reproducibles
     ///
     /// # Returns
     /// Regresa una tupla (testing_dataset: Dataset, training_dataset: Dataset, feature_names: Vec<String>)
+    /// o el error de `train_test_split_con_rng`
     pub fn initialize_datasets(
         num_features: usize,
         num_samples: usize,
@@ -53,42 +87,38 @@ impl Dataset {
         max: f64,
         feature_names: Vec<String>,
         possible_classes: Vec<String>,
-    ) -> (Dataset, Dataset, Vec<String>) {
-        let mut rng = rand::thread_rng();
+        random_seed: Option<u64>,
+    ) -> Result<(Dataset, Dataset, Vec<String>), MlError> {
+        let mut rng = crear_rng(random_seed);
+        let total_samples = num_samples * 2;
 
-        // Generate random data for training
-        let training_data = Self::random_f64_matrix(num_features, num_samples, min, max);
+        // Generate random data for both datasets at once
+        let data = Self::random_f64_matrix(num_features, total_samples, min, max, &mut rng);
 
-        // Generate random classes for training data
-        let training_labels: Vec<String> = (0..num_samples)
+        // Generate random classes for the data
+        let labels: Vec<String> = (0..total_samples)
             .map(|_| possible_classes[rng.gen_range(0..possible_classes.len())].clone())
             .collect();
 
-        let training_dataset = Dataset::new(
-            training_data.clone(),
-            feature_names.clone(),
-            training_labels,
-        );
+        let dataset = Dataset::new(data, feature_names.clone(), labels);
 
-        // Generate random data for testing
-        let testing_data = Self::random_f64_matrix(num_features, num_samples, min, max);
+        // Split in half: training and testing
+        let (training_dataset, testing_dataset) =
+            dataset.train_test_split_con_rng(0.5, false, &mut rng)?;
 
-        // Generate random classes for testing data
-        let testing_labels: Vec<String> = (0..num_samples)
-            .map(|_| possible_classes[rng.gen_range(0..possible_classes.len())].clone())
-            .collect();
-
-        // Create testing dataset
-        let testing_dataset = Dataset::new(testing_data, feature_names.clone(), testing_labels);
-
-        (testing_dataset, training_dataset, feature_names)
+        Ok((testing_dataset, training_dataset, feature_names))
     }
 
     // Helper function to generate a random matrix of f64 values
-    fn random_f64_matrix(features: usize, samples: usize, min: f64, max: f64) -> Vec<Vec<f64>> {
-        let mut rng = rand::thread_rng();
+    fn random_f64_matrix<R: Rng>(
+        features: usize,
+        samples: usize,
+        min: f64,
+        max: f64,
+        rng: &mut R,
+    ) -> Vec<Vec<f64>> {
         (0..samples)
-            .map(|_| (0..features).map(|_| rng.gen_range(min..max)).collect())
+            .map(|_| lista_con_rng(features, min, max, rng))
             .collect()
     }
 }
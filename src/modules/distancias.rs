@@ -1,10 +1,327 @@
-pub fn euclidean_distance(a: &[f64], b: &[f64]) -> f64 {
-    if a.len() != b.len() {
-        panic!("euclidean_distance: Vectores no son del mismo tamaño");
-    }
-    a.iter()
+use std::cmp::Ordering;
+
+use nalgebra::{DMatrix, DVector};
+
+use super::error::{check_dimensions, MlError};
+
+pub fn euclidean_distance(a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+    Ok(squared_euclidean_distance(a, b)?.sqrt())
+}
+
+/// Distancia euclidiana al cuadrado, evita la raiz cuando solo se comparan
+/// magnitudes (inercia, K-means++)
+pub fn squared_euclidean_distance(a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+    check_dimensions(a, b)?;
+    Ok(a.iter()
         .zip(b.iter())
         .map(|(x, y)| (x - y).powi(2))
-        .sum::<f64>()
-        .sqrt()
+        .sum::<f64>())
+}
+
+/// Orden total para distancias donde NaN siempre queda al final
+///
+/// `f64::total_cmp` ordena un NaN negativo antes que cualquier numero, asi que
+/// un NaN podria quedar como "vecino mas cercano"; aqui se trata como +∞
+pub fn comparar_distancias(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.total_cmp(&b),
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+    }
+}
+
+/// Metrica de distancia entre dos vectores del mismo tamaño
+///
+/// KNN y K-means reciben `&dyn Distance`, asi cualquier metrica puede usarse en
+/// lugar de la euclidiana. `Send + Sync` permite compartirla entre hilos.
+pub trait Distance: Send + Sync {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError>;
+
+    /// `true` si d(a, c) <= d(a, b) + d(b, c) para cualquier a, b, c
+    ///
+    /// Las cotas de K-means Elkan solo son validas con esta propiedad; por
+    /// defecto es `false`, asi una metrica nueva usa Lloyd hasta que se declare
+    fn satisfies_triangle_inequality(&self) -> bool {
+        false
+    }
+}
+
+/// sqrt(Σ (a_i - b_i)²)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+impl Distance for Euclidean {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        euclidean_distance(a, b)
+    }
+
+    fn satisfies_triangle_inequality(&self) -> bool {
+        true
+    }
+}
+
+/// Σ |a_i - b_i|
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Distance for Manhattan {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        check_dimensions(a, b)?;
+        Ok(a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum())
+    }
+
+    fn satisfies_triangle_inequality(&self) -> bool {
+        true
+    }
+}
+
+/// max |a_i - b_i|
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chebyshev;
+
+impl Distance for Chebyshev {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        check_dimensions(a, b)?;
+        Ok(a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f64::max))
+    }
+
+    fn satisfies_triangle_inequality(&self) -> bool {
+        true
+    }
+}
+
+/// (Σ |a_i - b_i|^p)^(1/p)
+///
+/// p = 1 es Manhattan, p = 2 es euclidiana y p = ∞ es Chebyshev
+#[derive(Debug, Clone, Copy)]
+pub struct Minkowski {
+    p: f64,
+}
+
+impl Minkowski {
+    /// Regresa Err(MlError::InvalidInput) si `p` es NaN o no es mayor a 0; p = ∞ es valido
+    pub fn new(p: f64) -> Result<Self, MlError> {
+        if p.is_nan() || p <= 0.0 {
+            return Err(MlError::InvalidInput(format!(
+                "p de Minkowski debe ser mayor a 0, se recibio {}",
+                p
+            )));
+        }
+        Ok(Minkowski { p })
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl Distance for Minkowski {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        if self.p.is_infinite() {
+            return Chebyshev.distance(a, b);
+        }
+        check_dimensions(a, b)?;
+        Ok(a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - y).abs().powf(self.p))
+            .sum::<f64>()
+            .powf(1.0 / self.p))
+    }
+
+    // Con p < 1 deja de ser metrica: d((1,0), (0,1)) = 2^(1/p) > d((1,0), 0) + d(0, (0,1)) = 2
+    fn satisfies_triangle_inequality(&self) -> bool {
+        self.p >= 1.0
+    }
+}
+
+/// 1 - (a · b) / (||a|| ||b||)
+///
+/// Ignora la magnitud de los vectores, solo compara su direccion.
+/// Si alguno de los vectores es cero la distancia es 1 (sin similitud),
+/// salvo que ambos sean cero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cosine;
+
+impl Distance for Cosine {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        check_dimensions(a, b)?;
+        let dot: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+        let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+        if norm_a == 0.0 && norm_b == 0.0 {
+            return Ok(0.0);
+        }
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return Ok(1.0);
+        }
+        Ok(1.0 - dot / (norm_a * norm_b))
+    }
+}
+
+/// Proporcion de coordenadas distintas entre los dos vectores
+///
+/// Pensada para features categoricas codificadas como numeros
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hamming;
+
+impl Distance for Hamming {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        check_dimensions(a, b)?;
+        if a.is_empty() {
+            return Ok(0.0);
+        }
+        let diferentes = a.iter().zip(b.iter()).filter(|(x, y)| x != y).count();
+        Ok(diferentes as f64 / a.len() as f64)
+    }
+
+    fn satisfies_triangle_inequality(&self) -> bool {
+        true
+    }
+}
+
+/// sqrt((a - b)ᵀ S⁻¹ (a - b)), con S la matriz de covarianza de los datos
+///
+/// Corrige diferencias de escala y correlacion entre features, util cuando
+/// las columnas tienen unidades muy distintas
+///
+/// `satisfies_triangle_inequality` queda en `false`: `new` acepta cualquier
+/// matriz cuadrada y solo una definida positiva da una metrica
+#[derive(Debug, Clone)]
+pub struct Mahalanobis {
+    inv_covarianza: DMatrix<f64>,
+}
+
+impl Mahalanobis {
+    /// Crea la metrica a partir de una matriz de covarianza ya invertida
+    ///
+    /// Regresa Err(MlError::InvalidInput) si la matriz no es cuadrada
+    pub fn new(inv_covarianza: DMatrix<f64>) -> Result<Self, MlError> {
+        if inv_covarianza.nrows() != inv_covarianza.ncols() {
+            return Err(MlError::InvalidInput(format!(
+                "La covarianza inversa debe ser cuadrada, se recibio {}x{}",
+                inv_covarianza.nrows(),
+                inv_covarianza.ncols()
+            )));
+        }
+        Ok(Mahalanobis { inv_covarianza })
+    }
+
+    /// Estima la covarianza de `data` (filas = muestras) y la invierte
+    ///
+    /// # Returns
+    /// - Err(MlError::InvalidInput) si hay menos de 2 muestras, no hay features o la
+    ///   covarianza es singular (p. ej. una columna constante)
+    /// - Err(MlError::DimensionMismatch) si las filas no tienen el mismo tamaño
+    pub fn from_data(data: &[Vec<f64>]) -> Result<Self, MlError> {
+        let n = data.len();
+        if n < 2 {
+            return Err(MlError::InvalidInput(format!(
+                "Se necesitan al menos 2 muestras para estimar la covarianza, se recibieron {}",
+                n
+            )));
+        }
+        let dim = data[0].len();
+        if dim == 0 {
+            return Err(MlError::InvalidInput(
+                "Las muestras no tienen features".to_string(),
+            ));
+        }
+        for row in data {
+            check_dimensions(&data[0], row)?;
+        }
+
+        let mut medias = vec![0.0; dim];
+        for row in data {
+            for (m, &x) in medias.iter_mut().zip(row.iter()) {
+                *m += x;
+            }
+        }
+        for m in medias.iter_mut() {
+            *m /= n as f64;
+        }
+
+        let covarianza = DMatrix::from_fn(dim, dim, |i, j| {
+            data.iter()
+                .map(|row| (row[i] - medias[i]) * (row[j] - medias[j]))
+                .sum::<f64>()
+                / (n - 1) as f64
+        });
+
+        let inv_covarianza = covarianza.try_inverse().ok_or_else(|| {
+            MlError::InvalidInput(
+                "La matriz de covarianza es singular (¿alguna columna constante?)".to_string(),
+            )
+        })?;
+        Mahalanobis::new(inv_covarianza)
+    }
+
+    pub fn inv_covarianza(&self) -> &DMatrix<f64> {
+        &self.inv_covarianza
+    }
+}
+
+impl Distance for Mahalanobis {
+    fn distance(&self, a: &[f64], b: &[f64]) -> Result<f64, MlError> {
+        check_dimensions(a, b)?;
+        if a.len() != self.inv_covarianza.nrows() {
+            return Err(MlError::DimensionMismatch {
+                expected: self.inv_covarianza.nrows(),
+                found: a.len(),
+            });
+        }
+        let diff = DVector::from_iterator(a.len(), a.iter().zip(b.iter()).map(|(x, y)| x - y));
+        // Con una covarianza valida el producto es >= 0, max evita NaN por redondeo
+        Ok(diff.dot(&(&self.inv_covarianza * &diff)).max(0.0).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minkowski_rechaza_p_invalido() {
+        for p in [0.0, -1.0, f64::NAN, f64::NEG_INFINITY] {
+            assert!(matches!(Minkowski::new(p), Err(MlError::InvalidInput(_))));
+        }
+        let chebyshev = Minkowski::new(f64::INFINITY).unwrap();
+        assert_eq!(chebyshev.distance(&[0.0, 0.0], &[3.0, -4.0]).unwrap(), 4.0);
+        let manhattan = Minkowski::new(1.0).unwrap();
+        assert_eq!(manhattan.distance(&[0.0, 0.0], &[3.0, -4.0]).unwrap(), 7.0);
+    }
+
+    #[test]
+    fn mahalanobis_rechaza_matriz_no_cuadrada() {
+        let no_cuadrada = DMatrix::from_element(2, 3, 1.0);
+        assert!(matches!(
+            Mahalanobis::new(no_cuadrada),
+            Err(MlError::InvalidInput(_))
+        ));
+
+        let identidad = Mahalanobis::new(DMatrix::identity(2, 2)).unwrap();
+        assert_eq!(identidad.distance(&[0.0, 0.0], &[3.0, 4.0]).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn mahalanobis_from_data_reporta_errores() {
+        assert!(matches!(
+            Mahalanobis::from_data(&[vec![1.0, 2.0]]),
+            Err(MlError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Mahalanobis::from_data(&[vec![1.0, 2.0], vec![3.0]]),
+            Err(MlError::DimensionMismatch { .. })
+        ));
+        // Columna constante: covarianza singular
+        assert!(matches!(
+            Mahalanobis::from_data(&[vec![1.0, 5.0], vec![2.0, 5.0], vec![4.0, 5.0]]),
+            Err(MlError::InvalidInput(_))
+        ));
+        assert!(Mahalanobis::from_data(&[vec![1.0, 5.0], vec![2.0, 3.0], vec![4.0, 8.0]]).is_ok());
+    }
 }
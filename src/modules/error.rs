@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// Error comun para todo el crate
+///
+/// Las funciones que antes hacian `panic!` o regresaban `Err(String)` regresan
+/// `Result<_, MlError>`, asi un dato mal formado no aborta todo el proceso.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MlError {
+    /// Dos vectores que debian tener el mismo tamaño no lo tienen
+    DimensionMismatch { expected: usize, found: usize },
+    /// `test_size` fuera del rango [0.0, 1.0]
+    InvalidTestSize(f64),
+    /// Atributo que no existe en la estructura de datos
+    UnknownAttribute(String),
+    /// Dataset sin muestras donde se requiere al menos una
+    EmptyDataset,
+    /// Celda de un CSV que no se pudo convertir a numero
+    ParseError {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    /// Error al leer o escribir un archivo CSV
+    Csv(String),
+    /// Cualquier otro parametro o combinacion de datos invalida
+    InvalidInput(String),
+    /// Modelo usado antes de llamar a `fit`
+    NotFitted,
+}
+
+impl fmt::Display for MlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MlError::DimensionMismatch { expected, found } => write!(
+                f,
+                "Vectores no son del mismo tamaño: se esperaba {}, se encontro {}",
+                expected, found
+            ),
+            MlError::InvalidTestSize(size) => {
+                write!(f, "Test size debe ser entre 0.0 y 1.0, se recibio {}", size)
+            }
+            MlError::UnknownAttribute(attribute) => {
+                write!(f, "Atributo '{}' no encontrado", attribute)
+            }
+            MlError::EmptyDataset => write!(f, "Dataset esta vacio"),
+            MlError::ParseError {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "Linea {}: valor '{}' en la columna '{}' no es numerico",
+                line, value, column
+            ),
+            MlError::Csv(msg) => write!(f, "Error de CSV: {}", msg),
+            MlError::InvalidInput(msg) => write!(f, "{}", msg),
+            MlError::NotFitted => write!(f, "El modelo no ha sido entrenado, llama a fit primero"),
+        }
+    }
+}
+
+impl std::error::Error for MlError {}
+
+impl From<csv::Error> for MlError {
+    fn from(err: csv::Error) -> Self {
+        MlError::Csv(err.to_string())
+    }
+}
+
+/// Verifica que dos vectores tengan el mismo tamaño
+pub fn check_dimensions(a: &[f64], b: &[f64]) -> Result<(), MlError> {
+    if a.len() != b.len() {
+        return Err(MlError::DimensionMismatch {
+            expected: a.len(),
+            found: b.len(),
+        });
+    }
+    Ok(())
+}
@@ -387,3 +387,107 @@ pub fn plot_3d(
     root.present()?;
     Ok(())
 }
+
+/// Grafica de silueta: barras horizontales ordenadas por cluster
+///
+/// Cada cluster se dibuja como un bloque de barras (una por punto) ordenadas de
+/// mayor a menor coeficiente, y una linea vertical marca la silueta promedio.
+pub fn plot_silueta(
+    coeficientes: &[f64],
+    cluster_assignments: &[usize],
+    filepath: &str,
+    title: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if coeficientes.len() != cluster_assignments.len() {
+        return Err(format!(
+            "Hay {} coeficientes pero {} asignaciones de cluster",
+            coeficientes.len(),
+            cluster_assignments.len()
+        )
+        .into());
+    }
+
+    let k = cluster_assignments
+        .iter()
+        .map(|&c| c + 1)
+        .max()
+        .unwrap_or(0);
+    let gap = 10usize; // Espacio vertical entre clusters
+
+    // Agrupar y ordenar coeficientes de cada cluster
+    let mut por_cluster: Vec<Vec<f64>> = vec![Vec::new(); k];
+    for (&s, &c) in coeficientes.iter().zip(cluster_assignments.iter()) {
+        por_cluster[c].push(s);
+    }
+    for valores in por_cluster.iter_mut() {
+        valores.sort_by(|a, b| b.total_cmp(a));
+    }
+
+    let total_height = coeficientes.len() + gap * (k + 1);
+    let x_min = coeficientes.iter().cloned().fold(0.0, f64::min).max(-1.0) - 0.1;
+    let promedio = if coeficientes.is_empty() {
+        0.0
+    } else {
+        coeficientes.iter().sum::<f64>() / coeficientes.len() as f64
+    };
+
+    let root = BitMapBackend::new(filepath, (800, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let caption = title.unwrap_or("Silhouette Plot");
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, ("Arial", 20))
+        .margin(20)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(x_min..1.0, 0..total_height)?;
+
+    chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .y_labels(0)
+        .x_desc("Silhouette coefficient")
+        .y_desc("Cluster")
+        .draw()?;
+
+    let mut y_lower = gap;
+    for (cluster_id, valores) in por_cluster.iter().enumerate() {
+        if valores.is_empty() {
+            continue;
+        }
+        let cluster_color = get_cluster_color(cluster_id);
+
+        chart
+            .draw_series(valores.iter().enumerate().map(|(i, &s)| {
+                Rectangle::new(
+                    [(0.0, y_lower + i), (s, y_lower + i + 1)],
+                    cluster_color.filled(),
+                )
+            }))?
+            .label(format!("Cluster {}", cluster_id))
+            .legend(move |(x, y)| {
+                Rectangle::new([(x, y - 5), (x + 10, y + 5)], cluster_color.filled())
+            });
+
+        y_lower += valores.len() + gap;
+    }
+
+    // Linea de silueta promedio
+    chart
+        .draw_series(LineSeries::new(
+            vec![(promedio, 0), (promedio, total_height)],
+            RED.stroke_width(2),
+        ))?
+        .label(format!("Mean = {:.3}", promedio))
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED.stroke_width(2)));
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .position(SeriesLabelPosition::LowerRight)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
@@ -1,2 +1,185 @@
-///Formula
-/// 
\ No newline at end of file
+use super::distancias::{squared_euclidean_distance, Euclidean};
+use super::error::MlError;
+use super::kmeans::{asignar_a_clusters, iterar_hasta_optimo};
+use super::verbosidad::Verbosity;
+
+/// Resultado del calculo de inercia (WCSS, within-cluster sum of squares)
+#[derive(Debug, Clone)]
+pub struct Inercia {
+    /// Suma de las distancias al cuadrado de cada punto a su centroide
+    pub total: f64,
+    /// Inercia de cada cluster, en el mismo orden que los centroides
+    pub por_cluster: Vec<f64>,
+}
+
+/// Calcula la inercia (WCSS) de una asignacion de K-means
+///
+/// # Formula:
+/// WCSS = Σ_k Σ_{x ∈ C_k} ||x - μ_k||²
+///
+/// # Parameters:
+/// - `data`: &[Vec<f64>] - The dataset that was clustered
+/// - `centroides`: &[Vec<f64>] - Centroids, e.g. the output of `iterar_hasta_optimo`
+/// - `clusters`: &[usize] - Cluster index for each point, e.g. the output of `asignar_a_clusters`
+///
+/// # Returns:
+/// - Ok(Inercia) - Total inertia and the inertia of each cluster
+/// - Err(MlError) - If `data` and `clusters` differ in length, a cluster index is out of range
+///   or a point and its centroid have a different number of features
+///
+/// # Notes:
+/// - Lower is better, but inertia always decreases as K grows, so only compare
+///   runs with the same K (or use the elbow method)
+pub fn calcular_inercia(
+    data: &[Vec<f64>],
+    centroides: &[Vec<f64>],
+    clusters: &[usize],
+) -> Result<Inercia, MlError> {
+    if data.len() != clusters.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Dataset tiene {} muestras pero {} asignaciones de cluster",
+            data.len(),
+            clusters.len()
+        )));
+    }
+
+    let mut por_cluster = vec![0.0; centroides.len()];
+
+    for (point, &cluster) in data.iter().zip(clusters.iter()) {
+        let centroid = centroides.get(cluster).ok_or_else(|| {
+            MlError::InvalidInput(format!(
+                "Cluster {} fuera de rango: solo hay {} centroides",
+                cluster,
+                centroides.len()
+            ))
+        })?;
+        por_cluster[cluster] += squared_euclidean_distance(point, centroid)?;
+    }
+
+    let total = por_cluster.iter().sum();
+
+    Ok(Inercia { total, por_cluster })
+}
+
+/// Resultado del metodo del codo
+#[derive(Debug, Clone)]
+pub struct ResultadoCodo {
+    /// Valores de K evaluados, en orden ascendente
+    pub ks: Vec<usize>,
+    /// Inercia total obtenida para cada K de `ks`
+    pub inercias: Vec<f64>,
+    /// K elegido en el punto de codo
+    pub k_optimo: usize,
+}
+
+/// Ejecuta KMeans para cada K en `k_min..=k_max` y elige K con el metodo del codo
+///
+/// # Algorithm Overview:
+/// 1. Runs `iterar_hasta_optimo` for every K and stores the total inertia
+/// 2. Normalizes K and inertia to [0, 1]
+/// 3. Picks the K whose point is farthest from the straight line joining the
+///    first and last points of the curve (kneedle-style knee detection)
+///
+/// # Parameters:
+/// - `data`: &[Vec<f64>] - The dataset to cluster
+/// - `k_min`, `k_max`: usize - Inclusive range of K values to try
+/// - `max_iters`: usize - Maximum iterations for each KMeans run
+/// - `random_seed`: Option<u64> - Seed used for every KMeans run, `None` uses system entropy
+///
+/// # Returns:
+/// - Ok(ResultadoCodo) - The inertia curve and the selected K
+/// - Err(MlError) - If the range is empty or invalid for the dataset size
+///
+/// # Notes:
+/// - With fewer than 3 K values there is no knee, so the smallest K is returned
+pub fn metodo_del_codo(
+    data: &[Vec<f64>],
+    k_min: usize,
+    k_max: usize,
+    max_iters: usize,
+    random_seed: Option<u64>,
+) -> Result<ResultadoCodo, MlError> {
+    if k_min == 0 || k_min > k_max {
+        return Err(MlError::InvalidInput(format!(
+            "Rango de K invalido: {}..={}",
+            k_min, k_max
+        )));
+    }
+    if k_max > data.len() {
+        return Err(MlError::InvalidInput(format!(
+            "K maximo ({}) mayor al numero de muestras ({})",
+            k_max,
+            data.len()
+        )));
+    }
+
+    let mut ks = Vec::new();
+    let mut inercias = Vec::new();
+
+    for k in k_min..=k_max {
+        let centroides = iterar_hasta_optimo(
+            max_iters,
+            data,
+            k,
+            &Euclidean,
+            random_seed,
+            Verbosity::Silent,
+        )?;
+        let clusters = asignar_a_clusters(data, &centroides, &Euclidean)?;
+        let inercia = calcular_inercia(data, &centroides, &clusters)?;
+        ks.push(k);
+        inercias.push(inercia.total);
+    }
+
+    let k_optimo = encontrar_codo(&ks, &inercias);
+
+    Ok(ResultadoCodo {
+        ks,
+        inercias,
+        k_optimo,
+    })
+}
+
+/// Encuentra el punto de codo de una curva decreciente
+///
+/// Normaliza ambos ejes y regresa el K con mayor distancia a la recta que une
+/// el primer y el ultimo punto
+pub fn encontrar_codo(ks: &[usize], inercias: &[f64]) -> usize {
+    if ks.is_empty() {
+        return 0;
+    }
+    if ks.len() < 3 || ks.len() != inercias.len() {
+        return ks[0];
+    }
+
+    let k_first = ks[0] as f64;
+    let k_span = (ks[ks.len() - 1] as f64 - k_first).max(f64::EPSILON);
+    let y_max = inercias.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let y_min = inercias.iter().cloned().fold(f64::INFINITY, f64::min);
+    let y_span = y_max - y_min;
+
+    if y_span <= 0.0 {
+        // Curva plana: agregar clusters no ayuda
+        return ks[0];
+    }
+
+    let puntos: Vec<(f64, f64)> = ks
+        .iter()
+        .zip(inercias.iter())
+        .map(|(&k, &y)| ((k as f64 - k_first) / k_span, (y - y_min) / y_span))
+        .collect();
+
+    let (x0, y0) = puntos[0];
+    let (x1, y1) = puntos[puntos.len() - 1];
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let norma = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+
+    // Distancia perpendicular de cada punto a la recta
+    puntos
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, y))| (i, (dy * x - dx * y + x1 * y0 - y1 * x0).abs() / norma))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| ks[i])
+        .unwrap_or(ks[0])
+}
@@ -0,0 +1,296 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::dataset_struct::Dataset;
+use super::distancias::{comparar_distancias, squared_euclidean_distance};
+use super::error::MlError;
+use super::knn_ia::Vecino;
+
+/// Indice espacial KD-tree para busquedas de vecinos mas cercanos (distancia euclidiana)
+///
+/// Se construye una sola vez a partir del dataset de entrenamiento en
+/// O(n log n) y cada consulta de k vecinos cuesta en promedio O(log n) para
+/// pocas dimensiones, en lugar de calcular las n distancias.
+///
+/// # Notes
+/// - Con muchas dimensiones (mas de ~20) la poda pierde efectividad y el
+///   costo se acerca al de fuerza bruta
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    rows: Vec<Vec<f64>>,
+    class_labels: Vec<String>,
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+    dim: usize,
+}
+
+#[derive(Debug, Clone)]
+struct KdNode {
+    /// Indice de la muestra en el dataset de entrenamiento
+    point: usize,
+    /// Eje (feature) sobre el que este nodo divide el espacio
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// Candidato a vecino, ordenado por distancia para usarlo en un max-heap
+#[derive(Debug, Clone, Copy)]
+struct Candidato {
+    distancia: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidato {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidato {}
+
+impl PartialOrd for Candidato {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidato {
+    fn cmp(&self, other: &Self) -> Ordering {
+        comparar_distancias(self.distancia, other.distancia).then(self.index.cmp(&other.index))
+    }
+}
+
+impl KdTree {
+    /// Construye el indice a partir de un dataset de entrenamiento con etiquetas
+    ///
+    /// # Returns
+    /// - Err(MlError::InvalidInput) si las etiquetas no coinciden con las filas
+    /// - Err(MlError::DimensionMismatch) si las filas no tienen todas el mismo tamaño
+    pub fn new(training_dataset: &Dataset) -> Result<Self, MlError> {
+        if training_dataset.rows.len() != training_dataset.class_labels.len() {
+            return Err(MlError::InvalidInput(format!(
+                "Training dataset has {} samples but {} class labels",
+                training_dataset.rows.len(),
+                training_dataset.class_labels.len()
+            )));
+        }
+
+        let dim = training_dataset.rows.first().map_or(0, |r| r.len());
+        if let Some(row) = training_dataset.rows.iter().find(|r| r.len() != dim) {
+            return Err(MlError::DimensionMismatch {
+                expected: dim,
+                found: row.len(),
+            });
+        }
+
+        let mut tree = KdTree {
+            rows: training_dataset.rows.clone(),
+            class_labels: training_dataset.class_labels.clone(),
+            nodes: Vec::with_capacity(training_dataset.rows.len()),
+            root: None,
+            dim,
+        };
+
+        let mut indices: Vec<usize> = (0..tree.rows.len()).collect();
+        tree.root = tree.build(&mut indices, 0);
+
+        Ok(tree)
+    }
+
+    /// Numero de muestras indexadas
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Filas indexadas, en el orden del dataset de entrenamiento
+    pub fn rows(&self) -> &[Vec<f64>] {
+        &self.rows
+    }
+
+    /// Numero de features de cada muestra
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    // Divide recursivamente por la mediana del eje actual
+    fn build(&mut self, indices: &mut [usize], depth: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+
+        let axis = if self.dim == 0 { 0 } else { depth % self.dim };
+        let mid = indices.len() / 2;
+
+        if self.dim > 0 {
+            let rows = &self.rows;
+            indices.select_nth_unstable_by(mid, |&a, &b| rows[a][axis].total_cmp(&rows[b][axis]));
+        }
+
+        let node_id = self.nodes.len();
+        self.nodes.push(KdNode {
+            point: indices[mid],
+            axis,
+            left: None,
+            right: None,
+        });
+
+        let (left, rest) = indices.split_at_mut(mid);
+        let left_id = self.build(left, depth + 1);
+        let right_id = self.build(&mut rest[1..], depth + 1);
+        self.nodes[node_id].left = left_id;
+        self.nodes[node_id].right = right_id;
+
+        Some(node_id)
+    }
+
+    /// Regresa los k vecinos mas cercanos a `target`, del mas cercano al mas lejano
+    ///
+    /// El formato es el mismo que `knn_ia::matrix_euclidean`:
+    /// (indice en el dataset de entrenamiento, distancia euclidiana, clase)
+    pub fn k_nearest(&self, target: &[f64], k: usize) -> Result<Vec<Vecino>, MlError> {
+        if k == 0 {
+            return Err(MlError::InvalidInput("K debe ser mayor a 0".to_string()));
+        }
+        if self.rows.is_empty() {
+            return Err(MlError::EmptyDataset);
+        }
+        if target.len() != self.dim {
+            return Err(MlError::DimensionMismatch {
+                expected: self.dim,
+                found: target.len(),
+            });
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.search(self.root, target, k, &mut heap)?;
+
+        Ok(heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|c| {
+                (
+                    c.index,
+                    c.distancia.sqrt(),
+                    self.class_labels[c.index].clone(),
+                )
+            })
+            .collect())
+    }
+
+    // Busqueda con poda: solo visita el otro lado del plano si puede contener
+    // un punto mas cercano que el peor candidato actual
+    fn search(
+        &self,
+        node: Option<usize>,
+        target: &[f64],
+        k: usize,
+        heap: &mut BinaryHeap<Candidato>,
+    ) -> Result<(), MlError> {
+        let node = match node {
+            Some(id) => &self.nodes[id],
+            None => return Ok(()),
+        };
+
+        let point = &self.rows[node.point];
+        let candidato = Candidato {
+            distancia: squared_euclidean_distance(target, point)?,
+            index: node.point,
+        };
+        if heap.len() < k {
+            heap.push(candidato);
+        } else if heap.peek().is_some_and(|peor| candidato < *peor) {
+            heap.pop();
+            heap.push(candidato);
+        }
+
+        if self.dim == 0 {
+            return Ok(());
+        }
+
+        let diff = target[node.axis] - point[node.axis];
+        let (near, far) = if diff < 0.0 {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+
+        self.search(near, target, k, heap)?;
+
+        let plano = diff * diff;
+        if heap.len() < k || heap.peek().is_some_and(|peor| plano <= peor.distancia) {
+            self.search(far, target, k, heap)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::create_dataset::{crear_rng, filas_de_prueba};
+    use crate::modules::distancias::Euclidean;
+    use crate::modules::knn_ia::k_nearest_indices;
+    use rand::Rng;
+
+    fn fuerza_bruta(dataset: &Dataset, target: &[f64], k: usize) -> Vec<Vecino> {
+        k_nearest_indices(&dataset.rows, target, k, &Euclidean)
+            .unwrap()
+            .into_iter()
+            .map(|(j, distancia)| (j, distancia, dataset.class_labels[j].clone()))
+            .collect()
+    }
+
+    fn dataset(n: usize, dim: usize, empates: bool, seed: u64) -> Dataset {
+        let labels = (0..n).map(|i| format!("C{}", i % 3)).collect();
+        Dataset::new(filas_de_prueba(n, dim, empates, seed), Vec::new(), labels)
+    }
+
+    #[test]
+    fn k_nearest_coincide_con_fuerza_bruta() {
+        let mut rng = crear_rng(Some(7));
+        for dim in 1..=5 {
+            for n in [1, 2, 9, 40, 120] {
+                let dataset = dataset(n, dim, true, (10 * dim + n) as u64);
+                let tree = KdTree::new(&dataset).unwrap();
+                for _ in 0..20 {
+                    let target: Vec<f64> = (0..dim).map(|_| rng.gen_range(-1..4) as f64).collect();
+                    for k in [1, 3, 7, n, n + 5] {
+                        assert_eq!(
+                            tree.k_nearest(&target, k).unwrap(),
+                            fuerza_bruta(&dataset, &target, k),
+                            "dim {}, n {}, k {}, target {:?}",
+                            dim,
+                            n,
+                            k,
+                            target
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_coincide_con_fuerza_bruta_en_datos_continuos() {
+        let mut rng = crear_rng(Some(11));
+        for dim in 1..=5 {
+            let dataset = dataset(200, dim, false, dim as u64);
+            let tree = KdTree::new(&dataset).unwrap();
+            for _ in 0..50 {
+                let target: Vec<f64> = (0..dim).map(|_| rng.gen_range(-60.0..60.0)).collect();
+                for k in [1, 5, 200, 250] {
+                    assert_eq!(
+                        tree.k_nearest(&target, k).unwrap(),
+                        fuerza_bruta(&dataset, &target, k)
+                    );
+                }
+            }
+        }
+    }
+}
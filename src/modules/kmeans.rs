@@ -1,7 +1,16 @@
 use std::collections::HashMap;
 
-use super::{dataset_struct::Dataset, distancias::euclidean_distance};
-use rand::Rng;
+use super::{
+    create_dataset::crear_rng,
+    dataset_struct::Dataset,
+    distancias::{Distance, Euclidean},
+    error::{check_dimensions, MlError},
+    inercia::calcular_inercia,
+    verbosidad::Verbosity,
+};
+use rand::{seq::index, seq::SliceRandom, Rng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Initializes centroids using the KMeans++ algorithm
 ///
@@ -12,112 +21,332 @@ use rand::Rng;
 /// 1. Randomly selects the first centroid from the dataset
 /// 2. For each subsequent centroid:
 ///    a. Calculates the minimum distance from each data point to any existing centroid
-///    b. Selects the next centroid with probability proportional to these distances
+///    b. Selects the next centroid with probability proportional to these distances squared
 ///       (points farther from existing centroids have higher probability of selection)
 ///
 /// # Parameters:
-/// - `data`: &Vec<Vec<f64>> - The dataset to calculate centroids from, where each inner vector
+/// - `data`: &[Vec<f64>] - The dataset to calculate centroids from, where each inner vector
 ///   represents a data point with multiple dimensions/features
 /// - `k`: usize - The number of clusters/centroids to generate
+/// - `metrica`: &dyn Distance - Metric used to measure distance to the existing centroids
+/// - `random_seed`: Option<u64> - Seed for reproducible results, `None` uses system entropy
+/// - `verbosity`: Verbosity - `Verbose` prints how many centroids were selected
 ///
 /// # Returns:
-/// - Vec<Vec<f64>> - A vector containing k centroids, where each centroid is a vector of f64 values
+/// - Ok(Vec<Vec<f64>>) - A vector containing k centroids, where each centroid is a vector of f64 values
 ///   representing a point in the same dimensional space as the input data
+/// - Err(MlError) - If a data point does not have the same number of features as the others
 ///
 /// # Notes:
 /// - The function handles edge cases (empty data, invalid k values)
 /// - Includes safeguards against numerical issues like division by zero
-pub fn kmeans_plus_plus(data: &Vec<Vec<f64>>, k: usize) -> Vec<Vec<f64>> {
+pub fn kmeans_plus_plus(
+    data: &[Vec<f64>],
+    k: usize,
+    metrica: &dyn Distance,
+    random_seed: Option<u64>,
+    verbosity: Verbosity,
+) -> Result<Vec<Vec<f64>>, MlError> {
     if data.is_empty() || k == 0 || k > data.len() {
-        return Vec::new();
+        return Ok(Vec::new());
+    }
+
+    let mut rng = crear_rng(random_seed);
+    let centroids = kmeans_plus_plus_con_rng(data, k, 1, metrica, &mut rng)?;
+
+    if verbosity.is_verbose() {
+        println!("Initial centroids selected: {} centroids", centroids.len());
+    }
+
+    Ok(centroids)
+}
+
+/// Initializes centroids using greedy KMeans++
+///
+/// # Algorithm Overview:
+/// Same as `kmeans_plus_plus`, but at every step `n_candidatos` points are sampled with
+/// D² weighting and the one that lowers the potential (sum of squared distances from each
+/// point to its closest centroid) the most is kept. This avoids most of the unlucky picks
+/// of plain KMeans++ at the cost of `n_candidatos` times more distance computations.
+///
+/// # Parameters:
+/// - `data`: &[Vec<f64>] - The dataset to calculate centroids from
+/// - `k`: usize - The number of clusters/centroids to generate
+/// - `n_candidatos`: usize - Candidates tried per step, `2 + ln(k)` is the usual choice
+/// - `metrica`: &dyn Distance - Metric used to measure distance to the existing centroids
+/// - `random_seed`: Option<u64> - Seed for reproducible results, `None` uses system entropy
+/// - `verbosity`: Verbosity - `Verbose` prints how many centroids were selected
+///
+/// # Returns:
+/// - Ok(Vec<Vec<f64>>) - k centroids, empty if `data` is empty or `k` is out of range
+/// - Err(MlError::InvalidInput) - If `n_candidatos` is 0
+/// - Err(MlError) - If a data point does not have the same number of features as the others
+pub fn kmeans_plus_plus_greedy(
+    data: &[Vec<f64>],
+    k: usize,
+    n_candidatos: usize,
+    metrica: &dyn Distance,
+    random_seed: Option<u64>,
+    verbosity: Verbosity,
+) -> Result<Vec<Vec<f64>>, MlError> {
+    if n_candidatos == 0 {
+        return Err(MlError::InvalidInput(
+            "n_candidatos debe ser mayor a 0".to_string(),
+        ));
+    }
+    if data.is_empty() || k == 0 || k > data.len() {
+        return Ok(Vec::new());
+    }
+
+    let mut rng = crear_rng(random_seed);
+    let centroids = kmeans_plus_plus_con_rng(data, k, n_candidatos, metrica, &mut rng)?;
+
+    if verbosity.is_verbose() {
+        println!(
+            "Initial centroids selected: {} centroids (greedy, {} candidates per step)",
+            centroids.len(),
+            n_candidatos
+        );
     }
 
-    let mut rng = rand::thread_rng();
+    Ok(centroids)
+}
+
+/// KMeans++ seeding shared by the plain (`n_candidatos == 1`) and greedy versions
+fn kmeans_plus_plus_con_rng<R: Rng>(
+    data: &[Vec<f64>],
+    k: usize,
+    n_candidatos: usize,
+    metrica: &dyn Distance,
+    rng: &mut R,
+) -> Result<Vec<Vec<f64>>, MlError> {
     let mut centroids = Vec::with_capacity(k);
 
     // Randomly select the first centroid from the dataset
-    centroids.push(data[rng.gen_range(0..data.len())].clone());
+    let primero = &data[rng.gen_range(0..data.len())];
+    centroids.push(primero.clone());
+
+    // Squared distance from each data point to its closest centroid so far
+    let mut cercanas = data
+        .iter()
+        .map(|point| Ok(metrica.distance(point, primero)?.powi(2)))
+        .collect::<Result<Vec<f64>, MlError>>()?;
 
     // Continue selecting centroids until we have k centroids
     while centroids.len() < k {
-        // For each data point, calculate the minimum distance to any existing centroid
-        let mut distances: Vec<f64> = data
-            .iter()
-            .map(|point| {
-                centroids
-                    .iter()
-                    .map(|c| euclidean_distance(point, c))
-                    .fold(f64::INFINITY, f64::min)
-            })
-            .collect();
-
-        // Prevent division by zero by checking total distance
-        let total_distance: f64 = distances.iter().sum();
-        if total_distance <= 0.0 {
-            // If all distances are zero, select a random point as a centroid
-            centroids.push(data[rng.gen_range(0..data.len())].clone());
-            continue;
-        }
+        // Keep the candidate whose addition leaves the smallest potential
+        let mut mejor: Option<(usize, Vec<f64>, f64)> = None;
+        for _ in 0..n_candidatos {
+            let candidato = muestrear_d2(&cercanas, rng);
+            let nuevas = data
+                .iter()
+                .zip(cercanas.iter())
+                .map(|(point, &actual)| {
+                    Ok(actual.min(metrica.distance(point, &data[candidato])?.powi(2)))
+                })
+                .collect::<Result<Vec<f64>, MlError>>()?;
+            let potencial: f64 = nuevas.iter().sum();
 
-        // Select next centroid with probability proportional to squared distance
-        // to the closest existing centroid
-        let mut cumulative_prob = 0.0;
-        let target = rng.gen::<f64>() * total_distance;
-        let mut selected = false;
-
-        for (i, &d) in distances.iter().enumerate() {
-            cumulative_prob += d;
-            if cumulative_prob >= target {
-                centroids.push(data[i].clone());
-                selected = true;
-                break;
+            if mejor.as_ref().is_none_or(|m| potencial < m.2) {
+                mejor = Some((candidato, nuevas, potencial));
             }
         }
 
-        // Safeguard against floating-point issues that might prevent selection
-        if !selected {
-            centroids.push(data[rng.gen_range(0..data.len())].clone());
+        if let Some((candidato, nuevas, _)) = mejor {
+            centroids.push(data[candidato].clone());
+            cercanas = nuevas;
         }
     }
 
-    println!("Initial centroids selected: {} centroids", centroids.len());
+    Ok(centroids)
+}
+
+/// Picks an index with probability proportional to `pesos` (the D² weights)
+fn muestrear_d2<R: Rng>(pesos: &[f64], rng: &mut R) -> usize {
+    // Prevent division by zero by checking the total weight
+    let total: f64 = pesos.iter().sum();
+    if total.is_nan() || total <= 0.0 {
+        // If all distances are zero, select a random point as a centroid
+        return rng.gen_range(0..pesos.len());
+    }
+
+    let target = rng.gen::<f64>() * total;
+    let mut acumulado = 0.0;
+    for (i, &peso) in pesos.iter().enumerate() {
+        acumulado += peso;
+        if acumulado >= target {
+            return i;
+        }
+    }
 
-    centroids
+    // Safeguard against floating-point issues that might prevent selection
+    rng.gen_range(0..pesos.len())
 }
 
-/// Assigns each data point to the nearest cluster using Euclidean distance
+/// How the initial centroids are chosen
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum KMeansInit {
+    /// `kmeans_plus_plus` seeding
+    #[default]
+    KMeansPlusPlus,
+    /// `kmeans_plus_plus_greedy` seeding with `n_candidatos` candidates per step
+    GreedyKMeansPlusPlus { n_candidatos: usize },
+    /// `k` distinct data points chosen uniformly at random
+    RandomPoints,
+    /// Means of a random partition of the data into `k` groups of (almost) equal size
+    RandomPartition,
+    /// User-supplied centroids, used as-is (every restart starts from them)
+    Centroids(Vec<Vec<f64>>),
+}
+
+/// Chooses `k` initial centroids for KMeans with the given strategy
+///
+/// # Parameters:
+/// - `data`: &[Vec<f64>] - The dataset to cluster
+/// - `k`: usize - The number of clusters
+/// - `init`: &KMeansInit - Initialization strategy
+/// - `metrica`: &dyn Distance - Metric used by the KMeans++ strategies
+/// - `random_seed`: Option<u64> - Seed for the random strategies, `None` uses system entropy
+/// - `verbosity`: Verbosity - `Verbose` prints how many centroids were selected
+///
+/// # Returns:
+/// - Ok(Vec<Vec<f64>>) - Exactly `k` centroids
+/// - Err(MlError::EmptyDataset) - If `data` has no rows
+/// - Err(MlError::InvalidInput) - If `k` is 0 or larger than the number of rows, or
+///   `KMeansInit::Centroids` does not hold exactly `k` centroids
+/// - Err(MlError::DimensionMismatch) - If a row or supplied centroid has a different
+///   number of features than the first row
+pub fn inicializar_centroides(
+    data: &[Vec<f64>],
+    k: usize,
+    init: &KMeansInit,
+    metrica: &dyn Distance,
+    random_seed: Option<u64>,
+    verbosity: Verbosity,
+) -> Result<Vec<Vec<f64>>, MlError> {
+    validar_k(data, k)?;
+
+    match init {
+        KMeansInit::KMeansPlusPlus => kmeans_plus_plus(data, k, metrica, random_seed, verbosity),
+        KMeansInit::GreedyKMeansPlusPlus { n_candidatos } => {
+            kmeans_plus_plus_greedy(data, k, *n_candidatos, metrica, random_seed, verbosity)
+        }
+        KMeansInit::RandomPoints => {
+            let mut rng = crear_rng(random_seed);
+            Ok(index::sample(&mut rng, data.len(), k)
+                .into_iter()
+                .map(|i| data[i].clone())
+                .collect())
+        }
+        KMeansInit::RandomPartition => {
+            let mut rng = crear_rng(random_seed);
+            let mut indices: Vec<usize> = (0..data.len()).collect();
+            indices.shuffle(&mut rng);
+
+            // Dealing the shuffled rows round-robin leaves no group empty
+            let mut clusters = vec![0; data.len()];
+            for (posicion, &i) in indices.iter().enumerate() {
+                clusters[i] = posicion % k;
+            }
+            for row in data {
+                check_dimensions(&data[0], row)?;
+            }
+            Ok(medias_por_cluster(data, &clusters, k).0)
+        }
+        KMeansInit::Centroids(centroides) => {
+            if centroides.len() != k {
+                return Err(MlError::InvalidInput(format!(
+                    "Se esperaban {} centroides iniciales, se recibieron {}",
+                    k,
+                    centroides.len()
+                )));
+            }
+            for centroide in centroides {
+                check_dimensions(&data[0], centroide)?;
+            }
+            Ok(centroides.clone())
+        }
+    }
+}
+
+/// Assigns each data point to the nearest cluster using the given metric
 ///
 /// # Algorithm Overview:
-/// For each data point, this function calculates the Euclidean distance to each centroid,
+/// For each data point, this function calculates the distance to each centroid,
 /// and assigns the point to the cluster with the nearest centroid.
 ///
 /// # Parameters:
-/// - `data`: Vec<Vec<f64>> - The dataset to assign to clusters
-/// - `centroides`: Vec<Vec<f64>> - The current centroids of each cluster
+/// - `data`: &[Vec<f64>] - The dataset to assign to clusters
+/// - `centroides`: &[Vec<f64>] - The current centroids of each cluster
+/// - `metrica`: &dyn Distance - Metric used to compare points and centroids (e.g. `&Euclidean`)
 ///
 /// # Returns:
-/// - Vec<usize> - A vector of cluster indices (0 to k-1) for each data point in the same order
+/// - Ok(Vec<usize>) - A vector of cluster indices (0 to k-1) for each data point in the same order
 ///   as the input data. Each value indicates which cluster the corresponding data point belongs to.
+/// - Err(MlError) - If a data point and the centroids have a different number of features
 ///
 /// # Notes:
 /// - Returns an empty vector if either input is empty
 /// - Uses min_by with partial_cmp to handle potential floating-point comparison issues
-pub fn asignar_a_clusters(data: Vec<Vec<f64>>, centroides: &Vec<Vec<f64>>) -> Vec<usize> {
+/// - With the `parallel` feature the points are split across rayon threads; the result is
+///   the same as the sequential one
+pub fn asignar_a_clusters(
+    data: &[Vec<f64>],
+    centroides: &[Vec<f64>],
+    metrica: &dyn Distance,
+) -> Result<Vec<usize>, MlError> {
     if data.is_empty() || centroides.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
 
-    data.iter()
-        .map(|point| {
-            centroides
-                .iter()
-                .enumerate()
-                .map(|(i, centroid)| (i, euclidean_distance(point, centroid)))
-                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-                .map(|(i, _)| i)
-                .unwrap_or(0) // Failsafe if min_by returns None
-        })
-        .collect()
+    #[cfg(feature = "parallel")]
+    let clusters = data
+        .par_iter()
+        .map(|point| centroide_mas_cercano(point, centroides, metrica))
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let clusters = data
+        .iter()
+        .map(|point| centroide_mas_cercano(point, centroides, metrica))
+        .collect();
+
+    clusters
+}
+
+/// Index of the centroid nearest to `point`; the first one wins ties
+pub(crate) fn centroide_mas_cercano(
+    point: &[f64],
+    centroides: &[Vec<f64>],
+    metrica: &dyn Distance,
+) -> Result<usize, MlError> {
+    let distancias = centroides
+        .iter()
+        .map(|centroid| metrica.distance(point, centroid))
+        .collect::<Result<Vec<f64>, MlError>>()?;
+
+    Ok(indice_minimo(&distancias))
+}
+
+/// Index of the smallest distance; the first one wins ties
+fn indice_minimo(distancias: &[f64]) -> usize {
+    distancias
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+        .unwrap_or(0) // Failsafe if min_by returns None
+}
+
+/// What `actualizar_centroides` does with a cluster that has no points assigned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyClusterStrategy {
+    /// Keep the centroid from the previous iteration
+    #[default]
+    KeepPrevious,
+    /// Move the centroid to the point farthest from its own cluster's centroid
+    FarthestPoint,
+    /// Split the cluster with the most points in two and give one half to the empty cluster
+    SplitLargest,
 }
 
 /// Updates centroids by calculating the mean of all points assigned to each cluster
@@ -128,30 +357,126 @@ pub fn asignar_a_clusters(data: Vec<Vec<f64>>, centroides: &Vec<Vec<f64>>) -> Ve
 /// 2. For each data point, adds its values to the corresponding cluster's centroid
 /// 3. Counts how many points are assigned to each cluster
 /// 4. Divides each centroid by its count to get the mean position
+/// 5. Recovers every cluster left without points using `estrategia`
 ///
 /// # Parameters:
-/// - `data`: &Vec<Vec<f64>> - The dataset
-/// - `clusters`: &Vec<usize> - The cluster assignments for each data point
-/// - `k`: usize - The number of clusters
+/// - `data`: &[Vec<f64>] - The dataset
+/// - `clusters`: &[usize] - The cluster assignments for each data point
+/// - `centroides_previos`: &[Vec<f64>] - Centroids used to compute `clusters`; k is their length
+/// - `estrategia`: EmptyClusterStrategy - How to recover clusters with no points
+/// - `metrica`: &dyn Distance - Metric used by `FarthestPoint` and `SplitLargest`
+/// - `verbosity`: Verbosity - `Verbose` prints a warning for centroids with no points
 ///
 /// # Returns:
-/// - Vec<Vec<f64>> - Updated centroids based on the mean of points in each cluster
+/// - Ok(Vec<Vec<f64>>) - Updated centroids based on the mean of points in each cluster
+/// - Err(MlError::InvalidInput) - If `data` and `clusters` have different lengths
+/// - Err(MlError) - If the metric fails to compare a point and a centroid
 ///
 /// # Notes:
-/// - Handles edge cases like empty data, invalid cluster assignments
-/// - Warns if a centroid has no points assigned to it
-/// - Maintains centroids with zero points assigned to avoid numerical issues
+/// - Returns `centroides_previos` unchanged if `data` is empty
+/// - Cluster indices outside 0..k are ignored
+/// - Empty clusters are handled in index order, so with `SplitLargest` a second empty
+///   cluster may split one of the halves of the first split
 pub fn actualizar_centroides(
-    data: &Vec<Vec<f64>>,
-    clusters: &Vec<usize>,
-    k: usize,
-) -> Vec<Vec<f64>> {
-    if data.is_empty() || clusters.is_empty() || data.len() != clusters.len() {
-        return vec![vec![0.0; data.get(0).map_or(0, |v| v.len())]; k];
+    data: &[Vec<f64>],
+    clusters: &[usize],
+    centroides_previos: &[Vec<f64>],
+    estrategia: EmptyClusterStrategy,
+    metrica: &dyn Distance,
+    verbosity: Verbosity,
+) -> Result<Vec<Vec<f64>>, MlError> {
+    if data.len() != clusters.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Dataset tiene {} muestras pero {} asignaciones de cluster",
+            data.len(),
+            clusters.len()
+        )));
     }
+    if data.is_empty() {
+        return Ok(centroides_previos.to_vec());
+    }
+
+    let k = centroides_previos.len();
+    let (mut nuevos_centroides, mut counts) = medias_por_cluster(data, clusters, k);
+    let mut asignacion = clusters.to_vec();
+
+    for i in 0..k {
+        if counts[i] > 0 {
+            continue;
+        }
+        if verbosity.is_verbose() {
+            println!(
+                "Warning: Centroid {} has no assigned points ({:?})",
+                i, estrategia
+            );
+        }
+
+        match estrategia {
+            EmptyClusterStrategy::KeepPrevious => {
+                nuevos_centroides[i] = centroides_previos[i].clone();
+            }
+            EmptyClusterStrategy::FarthestPoint => {
+                // Farthest point from its own centroid, not already the only point of its cluster
+                let mut lejano: Option<(usize, f64)> = None;
+                for (j, point) in data.iter().enumerate() {
+                    let c = asignacion[j];
+                    if c >= k || counts[c] < 2 {
+                        continue;
+                    }
+                    let d = metrica.distance(point, &nuevos_centroides[c])?;
+                    if lejano.is_none_or(|(_, max)| d > max) {
+                        lejano = Some((j, d));
+                    }
+                }
+
+                match lejano {
+                    Some((j, _)) => {
+                        // The point moves to the empty cluster; the cluster it leaves gets
+                        // its mean recomputed so later empty clusters measure against it
+                        let origen = asignacion[j];
+                        asignacion[j] = i;
+                        let (medias, nuevos_counts) = medias_por_cluster(data, &asignacion, k);
+                        for c in [origen, i] {
+                            nuevos_centroides[c] = medias[c].clone();
+                            counts[c] = nuevos_counts[c];
+                        }
+                    }
+                    None => nuevos_centroides[i] = centroides_previos[i].clone(),
+                }
+            }
+            EmptyClusterStrategy::SplitLargest => {
+                let grande = (0..k).max_by_key(|&c| (counts[c], std::cmp::Reverse(c)));
+                match grande {
+                    Some(grande) if counts[grande] >= 2 => {
+                        dividir_cluster(
+                            data,
+                            &mut asignacion,
+                            &mut nuevos_centroides,
+                            &mut counts,
+                            grande,
+                            i,
+                            metrica,
+                        )?;
+                    }
+                    _ => nuevos_centroides[i] = centroides_previos[i].clone(),
+                }
+            }
+        }
+    }
+
+    Ok(nuevos_centroides)
+}
 
-    let dim = data[0].len();
-    let mut nuevos_centroides = vec![vec![0.0; dim]; k];
+/// Mean of the points of each cluster and the number of points per cluster
+///
+/// Empty clusters get a zero vector; indices outside 0..k are ignored.
+fn medias_por_cluster(
+    data: &[Vec<f64>],
+    clusters: &[usize],
+    k: usize,
+) -> (Vec<Vec<f64>>, Vec<usize>) {
+    let dim = data.first().map_or(0, |v| v.len());
+    let mut medias = vec![vec![0.0; dim]; k];
     let mut counts = vec![0; k];
 
     // Sum up all points assigned to each cluster
@@ -161,7 +486,7 @@ pub fn actualizar_centroides(
             for (i, &value) in point.iter().enumerate() {
                 if i < dim {
                     // Validate dimension index is in bounds
-                    nuevos_centroides[cluster][i] += value;
+                    medias[cluster][i] += value;
                 }
             }
             counts[cluster] += 1;
@@ -169,19 +494,67 @@ pub fn actualizar_centroides(
     }
 
     // Calculate the mean position for each cluster
-    for (i, centroid) in nuevos_centroides.iter_mut().enumerate() {
-        if counts[i] > 0 {
-            for value in centroid.iter_mut() {
-                *value /= counts[i] as f64;
+    for (media, &count) in medias.iter_mut().zip(counts.iter()) {
+        if count > 0 {
+            for value in media.iter_mut() {
+                *value /= count as f64;
             }
-        } else {
-            // If a centroid has no points assigned, keep it unchanged
-            // (This can happen and may indicate a suboptimal k value)
-            println!("Warning: Centroid {} has no assigned points", i);
         }
     }
 
-    nuevos_centroides
+    (medias, counts)
+}
+
+/// Splits cluster `origen` in two, moving the half nearest to its farthest point to `destino`
+///
+/// The points closer to the farthest point than to the current centroid form the new
+/// cluster; if that leaves one side empty (e.g. all points are equal) only the farthest
+/// point is moved.
+fn dividir_cluster(
+    data: &[Vec<f64>],
+    asignacion: &mut [usize],
+    centroides: &mut [Vec<f64>],
+    counts: &mut [usize],
+    origen: usize,
+    destino: usize,
+    metrica: &dyn Distance,
+) -> Result<(), MlError> {
+    let miembros: Vec<usize> = (0..data.len())
+        .filter(|&j| asignacion[j] == origen)
+        .collect();
+
+    let mut lejano = miembros[0];
+    let mut max_distancia = f64::NEG_INFINITY;
+    for &j in &miembros {
+        let d = metrica.distance(&data[j], &centroides[origen])?;
+        if d > max_distancia {
+            lejano = j;
+            max_distancia = d;
+        }
+    }
+
+    let mut mitad = Vec::new();
+    for &j in &miembros {
+        let al_lejano = metrica.distance(&data[j], &data[lejano])?;
+        let al_centroide = metrica.distance(&data[j], &centroides[origen])?;
+        if al_lejano < al_centroide {
+            mitad.push(j);
+        }
+    }
+    if mitad.is_empty() || mitad.len() == miembros.len() {
+        mitad = vec![lejano];
+    }
+
+    for &j in &mitad {
+        asignacion[j] = destino;
+    }
+    let (medias, nuevos_counts) = medias_por_cluster(data, asignacion, centroides.len());
+    for c in [origen, destino] {
+        centroides[c] = medias[c].clone();
+        counts[c] = nuevos_counts[c];
+    }
+
+    Ok(())
 }
 
 /// Iterates the KMeans algorithm until convergence or maximum iterations are reached
@@ -196,70 +569,661 @@ pub fn actualizar_centroides(
 ///
 /// # Parameters:
 /// - `max_iters`: usize - Maximum number of iterations
-/// - `data`: Vec<Vec<f64>> - The dataset to cluster
+/// - `data`: &[Vec<f64>] - The dataset to cluster
 /// - `k`: usize - The number of clusters
+/// - `metrica`: &dyn Distance - Metric used for initialization and assignment
+/// - `random_seed`: Option<u64> - Seed for the KMeans++ initialization, `None` uses system entropy
+/// - `verbosity`: Verbosity - `Verbose` prints initialization, convergence and empty-cluster messages
 ///
 /// # Returns:
-/// - Vec<Vec<f64>> - Final centroids after convergence or max iterations
+/// - Ok(Vec<Vec<f64>>) - Final centroids after convergence or max iterations
+/// - Err(MlError) - If the rows of `data` do not all have the same number of features
 /// Explicacion por que se me olvida:
 /// Regresa algo similar a coordenadas (x,y) si son 2 clases o columnas
 /// (X,Y,Z) si son 3 columnas y asi consecutivamente
 ///
 /// # Notes:
+/// - Centroids are always updated as the mean, so non-Euclidean metrics only change
+///   initialization and assignment
 /// - Convergence is checked by comparing old and new centroids
-/// - A small threshold (1e-6) is used to account for floating-point precision
+/// - A small threshold (`TOLERANCIA_POR_DEFECTO`, 1e-6) is used to account for floating-point
+///   precision; use `KMeans` to choose a different tolerance
+/// - A cluster that loses all its points keeps its previous centroid
+///   (`EmptyClusterStrategy::KeepPrevious`); `KMeans` can choose another strategy
 /// - The algorithm can terminate early if centroids stop changing
-pub fn iterar_hasta_optimo(max_iters: usize, data: Vec<Vec<f64>>, k: usize) -> Vec<Vec<f64>> {
+pub fn iterar_hasta_optimo(
+    max_iters: usize,
+    data: &[Vec<f64>],
+    k: usize,
+    metrica: &dyn Distance,
+    random_seed: Option<u64>,
+    verbosity: Verbosity,
+) -> Result<Vec<Vec<f64>>, MlError> {
     if data.is_empty() || k == 0 || k > data.len() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
 
     // Initialize centroids using KMeans++
-    let mut centroids = kmeans_plus_plus(&data, k);
+    let centroids = kmeans_plus_plus(data, k, metrica, random_seed, verbosity)?;
+    let (centroids, _) = iterar_lloyd(
+        data,
+        centroids,
+        max_iters,
+        TOLERANCIA_POR_DEFECTO,
+        EmptyClusterStrategy::default(),
+        metrica,
+        verbosity,
+    )?;
+
+    Ok(centroids)
+}
+
+/// Convergence threshold used by `iterar_hasta_optimo` and as the `KMeans` default
+pub const TOLERANCIA_POR_DEFECTO: f64 = 1e-6;
+
+/// Lloyd iterations (assign, then update) starting from the given centroids
+///
+/// Stops when no centroid coordinate moves more than `tolerance` or after `max_iters`
+/// iterations, and returns the final centroids with the number of iterations run.
+fn iterar_lloyd(
+    data: &[Vec<f64>],
+    mut centroids: Vec<Vec<f64>>,
+    max_iters: usize,
+    tolerance: f64,
+    empty_cluster: EmptyClusterStrategy,
+    metrica: &dyn Distance,
+    verbosity: Verbosity,
+) -> Result<(Vec<Vec<f64>>, usize), MlError> {
     let mut iteration = 0;
 
     // Main KMeans iteration loop
     while iteration < max_iters {
         // Assign data points to nearest centroid
-        let clusters = asignar_a_clusters(data.clone(), &centroids);
+        let clusters = asignar_a_clusters(data, &centroids, metrica)?;
 
         // Update centroids based on cluster assignments
-        let new_centroids = actualizar_centroides(&data, &clusters, k);
-
-        // Check for convergence (centroids no longer moving significantly)
-        let mut converged = true;
-        for (i, centroid) in centroids.iter().enumerate() {
-            for (j, &value) in centroid.iter().enumerate() {
-                if (value - new_centroids[i][j]).abs() > 1e-6 {
-                    converged = false;
-                    break;
+        let new_centroids = actualizar_centroides(
+            data,
+            &clusters,
+            &centroids,
+            empty_cluster,
+            metrica,
+            verbosity,
+        )?;
+
+        // If converged (centroids no longer moving significantly), exit early
+        if convergieron(&centroids, &new_centroids, tolerance) {
+            if verbosity.is_verbose() {
+                println!("KMeans converged after {} iterations", iteration + 1);
+            }
+            return Ok((centroids, iteration + 1));
+        }
+
+        // Otherwise, update centroids and continue
+        centroids = new_centroids;
+        iteration += 1;
+    }
+
+    // Notify if reached maximum iterations without convergence
+    if verbosity.is_verbose() {
+        println!(
+            "KMeans reached maximum iterations ({}) without converging",
+            max_iters
+        );
+    }
+
+    Ok((centroids, iteration))
+}
+
+/// True if no coordinate moved more than `tolerance` between `anteriores` and `nuevos`
+fn convergieron(anteriores: &[Vec<f64>], nuevos: &[Vec<f64>], tolerance: f64) -> bool {
+    anteriores
+        .iter()
+        .zip(nuevos.iter())
+        .all(|(anterior, nuevo)| {
+            anterior
+                .iter()
+                .zip(nuevo.iter())
+                .all(|(a, b)| (a - b).abs() <= tolerance)
+        })
+}
+
+/// Relative slack that keeps Elkan's bounds valid despite floating-point rounding
+const HOLGURA_COTAS: f64 = 1e-10;
+
+/// True if `cota` proves that a centroid is strictly farther than `superior`
+fn descartado(superior: f64, cota: f64) -> bool {
+    superior + HOLGURA_COTAS * (superior + cota) < cota
+}
+
+/// Lloyd iterations with Elkan's triangle-inequality bounds
+///
+/// # Algorithm Overview:
+/// Every point keeps an upper bound on the distance to its own centroid and a lower
+/// bound on the distance to every other centroid. Since
+/// d(x, c_j) >= d(c_a, c_j) - d(x, c_a), a centroid can be skipped when the upper bound
+/// is below its lower bound or below half its distance to the assigned centroid. After
+/// the centroids move, the bounds are loosened by how far each centroid moved instead
+/// of being recomputed.
+///
+/// # Notes:
+/// - Gives the same assignments, centroids and iteration count as `iterar_lloyd` as long
+///   as the metric satisfies the triangle inequality (not `Cosine`) and the data is finite;
+///   `KMeans::fit` checks `Distance::satisfies_triangle_inequality` before calling it
+/// - A centroid is only skipped when the bounds prove it is strictly farther, so ties are
+///   broken by index exactly like `asignar_a_clusters`
+/// - Uses O(n * k) extra memory for the lower bounds
+fn iterar_elkan(
+    data: &[Vec<f64>],
+    mut centroids: Vec<Vec<f64>>,
+    max_iters: usize,
+    tolerance: f64,
+    empty_cluster: EmptyClusterStrategy,
+    metrica: &dyn Distance,
+    verbosity: Verbosity,
+) -> Result<(Vec<Vec<f64>>, usize), MlError> {
+    let k = centroids.len();
+
+    // Exact first assignment: every distance is a tight lower bound
+    let mut inferiores = data
+        .iter()
+        .map(|point| {
+            centroids
+                .iter()
+                .map(|centroid| metrica.distance(point, centroid))
+                .collect::<Result<Vec<f64>, MlError>>()
+        })
+        .collect::<Result<Vec<Vec<f64>>, MlError>>()?;
+    let mut clusters: Vec<usize> = inferiores.iter().map(|d| indice_minimo(d)).collect();
+    let mut superiores: Vec<f64> = clusters
+        .iter()
+        .zip(inferiores.iter())
+        .map(|(&a, d)| d[a])
+        .collect();
+    let mut calculadas = data.len() * k;
+    let mut iteration = 0;
+
+    while iteration < max_iters {
+        if iteration > 0 {
+            // Half the distance from each centroid to its nearest other centroid
+            let mut entre = vec![vec![0.0; k]; k];
+            for a in 0..k {
+                for b in (a + 1)..k {
+                    let d = metrica.distance(&centroids[a], &centroids[b])?;
+                    entre[a][b] = d;
+                    entre[b][a] = d;
                 }
             }
-            if !converged {
-                break;
+            calculadas += k * (k - 1) / 2;
+            let mitad_vecino: Vec<f64> = (0..k)
+                .map(|a| {
+                    (0..k)
+                        .filter(|&b| b != a)
+                        .map(|b| 0.5 * entre[a][b])
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect();
+
+            for (i, point) in data.iter().enumerate() {
+                let mut a = clusters[i];
+                if descartado(superiores[i], mitad_vecino[a]) {
+                    continue;
+                }
+
+                let mut exacta = false;
+                for j in 0..k {
+                    if j == a
+                        || descartado(superiores[i], inferiores[i][j])
+                        || descartado(superiores[i], 0.5 * entre[a][j])
+                    {
+                        continue;
+                    }
+
+                    // Tighten the upper bound once before comparing against other centroids
+                    if !exacta {
+                        superiores[i] = metrica.distance(point, &centroids[a])?;
+                        inferiores[i][a] = superiores[i];
+                        calculadas += 1;
+                        exacta = true;
+                        if descartado(superiores[i], inferiores[i][j])
+                            || descartado(superiores[i], 0.5 * entre[a][j])
+                        {
+                            continue;
+                        }
+                    }
+
+                    let d = metrica.distance(point, &centroids[j])?;
+                    inferiores[i][j] = d;
+                    calculadas += 1;
+                    if d < superiores[i] || (d == superiores[i] && j < a) {
+                        a = j;
+                        superiores[i] = d;
+                    }
+                }
+                clusters[i] = a;
             }
         }
 
-        // If converged, exit early
-        if converged {
-            println!("KMeans converged after {} iterations", iteration + 1);
-            break;
+        // Update centroids based on cluster assignments
+        let new_centroids = actualizar_centroides(
+            data,
+            &clusters,
+            &centroids,
+            empty_cluster,
+            metrica,
+            verbosity,
+        )?;
+
+        // If converged (centroids no longer moving significantly), exit early
+        if convergieron(&centroids, &new_centroids, tolerance) {
+            if verbosity.is_verbose() {
+                println!(
+                    "KMeans (Elkan) converged after {} iterations, {} of {} distances computed",
+                    iteration + 1,
+                    calculadas,
+                    (iteration + 1) * data.len() * k
+                );
+            }
+            return Ok((centroids, iteration + 1));
+        }
+
+        // Loosen the bounds by how far each centroid moved
+        let desplazamientos = centroids
+            .iter()
+            .zip(new_centroids.iter())
+            .map(|(anterior, nuevo)| metrica.distance(anterior, nuevo))
+            .collect::<Result<Vec<f64>, MlError>>()?;
+        calculadas += k;
+        for (i, &a) in clusters.iter().enumerate() {
+            let (superior, p) = (superiores[i], desplazamientos[a]);
+            superiores[i] = superior + p + HOLGURA_COTAS * (superior + p);
+            for (inferior, &p) in inferiores[i].iter_mut().zip(desplazamientos.iter()) {
+                *inferior = (*inferior - p - HOLGURA_COTAS * (*inferior + p)).max(0.0);
+            }
         }
 
-        // Otherwise, update centroids and continue
         centroids = new_centroids;
         iteration += 1;
     }
 
     // Notify if reached maximum iterations without convergence
-    if iteration == max_iters {
+    if verbosity.is_verbose() {
         println!(
-            "KMeans reached maximum iterations ({}) without converging",
-            max_iters
+            "KMeans (Elkan) reached maximum iterations ({}) without converging, {} of {} distances computed",
+            max_iters,
+            calculadas,
+            max_iters * data.len() * k
         );
     }
 
-    centroids
+    Ok((centroids, iteration))
+}
+
+/// Which iteration `KMeans::fit` runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KMeansAlgorithm {
+    /// Plain assign / update iterations
+    #[default]
+    Lloyd,
+    /// Same results as `Lloyd`, skipping most distance computations with
+    /// triangle-inequality bounds; needs O(n * k) memory. `KMeans::fit` falls back to
+    /// `Lloyd` when `Distance::satisfies_triangle_inequality` is false (e.g. `Cosine`)
+    Elkan,
+}
+
+/// `data` is not empty and `k` is between 1 and the number of rows
+fn validar_k(data: &[Vec<f64>], k: usize) -> Result<(), MlError> {
+    if data.is_empty() {
+        return Err(MlError::EmptyDataset);
+    }
+    if k == 0 || k > data.len() {
+        return Err(MlError::InvalidInput(format!(
+            "K debe estar entre 1 y {} (numero de muestras), se recibio {}",
+            data.len(),
+            k
+        )));
+    }
+    Ok(())
+}
+
+/// Training data checks shared by `KMeans`, `MiniBatchKMeans` and `KMedoids`
+///
+/// # Returns:
+/// - Err(MlError::EmptyDataset) - If `data` has no rows
+/// - Err(MlError::InvalidInput) - If `k` is 0 or larger than the number of rows
+/// - Err(MlError::DimensionMismatch) - If the rows do not all have the same number of features
+pub(crate) fn validar_datos(data: &[Vec<f64>], k: usize) -> Result<(), MlError> {
+    validar_k(data, k)?;
+    for row in data {
+        check_dimensions(&data[0], row)?;
+    }
+    Ok(())
+}
+
+/// Err(MlError::InvalidInput) if the convergence tolerance is negative or NaN
+pub(crate) fn validar_tolerancia(tolerance: f64) -> Result<(), MlError> {
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(MlError::InvalidInput(format!(
+            "Tolerancia debe ser mayor o igual a 0, se recibio {}",
+            tolerance
+        )));
+    }
+    Ok(())
+}
+
+/// Distance from each row of `data` to every center, the `transform` of the clustering models
+///
+/// # Returns:
+/// - Ok(Vec<Vec<f64>>) - One row per point with one distance per center, in center order
+pub(crate) fn distancias_a_centros(
+    data: &[Vec<f64>],
+    centros: &[Vec<f64>],
+    metrica: &dyn Distance,
+) -> Result<Vec<Vec<f64>>, MlError> {
+    data.iter()
+        .map(|point| {
+            centros
+                .iter()
+                .map(|centro| metrica.distance(point, centro))
+                .collect()
+        })
+        .collect()
+}
+
+/// Implements `with_metric` for a clustering model generic over its metric
+///
+/// Takes the model name, every field except `metrica` and the fitted state, and the
+/// name of the fitted-state field, which is reset because it used the old metric.
+macro_rules! impl_with_metric {
+    ($modelo:ident { $($campo:ident),* $(,)? }, $ajuste:ident) => {
+        impl<D: $crate::modules::distancias::Distance> $modelo<D> {
+            /// Replaces the metric; discards any previous fit because it used the old metric
+            pub fn with_metric<M: $crate::modules::distancias::Distance>(
+                self,
+                metrica: M,
+            ) -> $modelo<M> {
+                $modelo {
+                    $($campo: self.$campo,)*
+                    metrica,
+                    $ajuste: None,
+                }
+            }
+        }
+    };
+}
+pub(crate) use impl_with_metric;
+
+/// State learned by `KMeans::fit`
+#[derive(Debug, Clone)]
+struct KMeansAjuste {
+    centroids: Vec<Vec<f64>>,
+    labels: Vec<usize>,
+    inertia: f64,
+    /// Sum of squared `metrica` distances to the assigned centroid, used to pick the best run
+    costo: f64,
+    n_iter: usize,
+}
+
+/// KMeans model that keeps its configuration and the result of the last `fit`
+///
+/// # Example:
+/// ```ignore
+/// let mut kmeans = KMeans::new(3)
+///     .with_max_iters(100)
+///     .with_n_init(10)
+///     .with_seed(Some(42))
+///     .with_metric(Manhattan);
+/// kmeans.fit(&training_data)?;
+/// let clusters = kmeans.predict(&testing_data)?;
+/// println!("Inercia: {:.4} en {} iteraciones", kmeans.inertia()?, kmeans.n_iter()?);
+/// ```
+///
+/// # Notes:
+/// - Fitted attributes return `Err(MlError::NotFitted)` until `fit` succeeds
+/// - Changing the configuration after `fit` does not reset the fitted attributes;
+///   call `fit` again to apply it
+#[derive(Debug, Clone)]
+pub struct KMeans<D: Distance = Euclidean> {
+    /// Number of clusters
+    pub k: usize,
+    /// Maximum number of Lloyd iterations
+    pub max_iters: usize,
+    /// Number of independently initialized runs; the one with the lowest cost is kept
+    pub n_init: usize,
+    /// Largest centroid coordinate change still considered converged
+    pub tolerance: f64,
+    /// Initialization strategy
+    pub init: KMeansInit,
+    /// What to do with clusters that lose all their points during an iteration
+    pub empty_cluster: EmptyClusterStrategy,
+    /// Lloyd or the equivalent, bound-accelerated Elkan iteration
+    pub algorithm: KMeansAlgorithm,
+    /// Seed for the initialization, `None` uses system entropy
+    pub random_seed: Option<u64>,
+    /// Metric used for initialization, assignment and `transform`
+    pub metrica: D,
+    /// `Verbose` prints initialization, convergence and empty-cluster messages
+    pub verbosity: Verbosity,
+    ajuste: Option<KMeansAjuste>,
+}
+
+impl KMeans<Euclidean> {
+    /// Euclidean KMeans with 300 max iterations, a single run, tolerance 1e-6, KMeans++ and no seed
+    pub fn new(k: usize) -> Self {
+        KMeans {
+            k,
+            max_iters: 300,
+            n_init: 1,
+            tolerance: TOLERANCIA_POR_DEFECTO,
+            init: KMeansInit::default(),
+            empty_cluster: EmptyClusterStrategy::default(),
+            algorithm: KMeansAlgorithm::default(),
+            random_seed: None,
+            metrica: Euclidean,
+            verbosity: Verbosity::Silent,
+            ajuste: None,
+        }
+    }
+}
+
+impl_with_metric!(
+    KMeans {
+        k,
+        max_iters,
+        n_init,
+        tolerance,
+        init,
+        empty_cluster,
+        algorithm,
+        random_seed,
+        verbosity,
+    },
+    ajuste
+);
+
+impl<D: Distance> KMeans<D> {
+    pub fn with_max_iters(mut self, max_iters: usize) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    pub fn with_n_init(mut self, n_init: usize) -> Self {
+        self.n_init = n_init;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_init(mut self, init: KMeansInit) -> Self {
+        self.init = init;
+        self
+    }
+
+    pub fn with_empty_cluster_strategy(mut self, empty_cluster: EmptyClusterStrategy) -> Self {
+        self.empty_cluster = empty_cluster;
+        self
+    }
+
+    pub fn with_algorithm(mut self, algorithm: KMeansAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn with_seed(mut self, random_seed: Option<u64>) -> Self {
+        self.random_seed = random_seed;
+        self
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Runs KMeans on `data` and stores centroids, labels, inertia and iteration count
+    ///
+    /// With `n_init > 1` the algorithm is run `n_init` times, run `i` seeded with
+    /// `random_seed + i`, and the run with the lowest cost is kept. The cost is the sum
+    /// of squared `metrica` distances from each row to its centroid, which is the
+    /// inertia when the metric is Euclidean.
+    ///
+    /// # Returns:
+    /// - Ok(()) - The model is fitted
+    /// - Err(MlError::EmptyDataset) - If `data` has no rows
+    /// - Err(MlError::InvalidInput) - If `k` or `n_init` is 0, `k` is larger than the number
+    ///   of rows, or `tolerance` is negative or NaN
+    /// - Err(MlError::DimensionMismatch) - If the rows do not all have the same number of features
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> Result<(), MlError> {
+        validar_tolerancia(self.tolerance)?;
+        if self.n_init == 0 {
+            return Err(MlError::InvalidInput(
+                "n_init debe ser mayor a 0".to_string(),
+            ));
+        }
+        validar_datos(data, self.k)?;
+        if self.algorithm == KMeansAlgorithm::Elkan
+            && !self.metrica.satisfies_triangle_inequality()
+            && self.verbosity.is_verbose()
+        {
+            println!("Metric breaks the triangle inequality, using Lloyd instead of Elkan");
+        }
+
+        // Each run gets its own seed so the runs are independent but reproducible
+        let mut mejor: Option<KMeansAjuste> = None;
+        for corrida in 0..self.n_init {
+            let semilla = self
+                .random_seed
+                .map(|seed| seed.wrapping_add(corrida as u64));
+            let ajuste = self.ajustar_una_vez(data, semilla)?;
+
+            if self.verbosity.is_verbose() && self.n_init > 1 {
+                println!(
+                    "Corrida {}/{}: costo {:.4}",
+                    corrida + 1,
+                    self.n_init,
+                    ajuste.costo
+                );
+            }
+
+            // Strict comparison keeps the earliest run on ties
+            if mejor.as_ref().is_none_or(|m| ajuste.costo < m.costo) {
+                mejor = Some(ajuste);
+            }
+        }
+
+        self.ajuste = mejor;
+        Ok(())
+    }
+
+    /// One initialization followed by Lloyd iterations
+    fn ajustar_una_vez(
+        &self,
+        data: &[Vec<f64>],
+        random_seed: Option<u64>,
+    ) -> Result<KMeansAjuste, MlError> {
+        let iniciales = inicializar_centroides(
+            data,
+            self.k,
+            &self.init,
+            &self.metrica,
+            random_seed,
+            self.verbosity,
+        )?;
+        let iterar = match self.algorithm {
+            KMeansAlgorithm::Elkan if self.metrica.satisfies_triangle_inequality() => iterar_elkan,
+            _ => iterar_lloyd,
+        };
+        let (centroids, n_iter) = iterar(
+            data,
+            iniciales,
+            self.max_iters,
+            self.tolerance,
+            self.empty_cluster,
+            &self.metrica,
+            self.verbosity,
+        )?;
+        let labels = asignar_a_clusters(data, &centroids, &self.metrica)?;
+        let inertia = calcular_inercia(data, &centroids, &labels)?.total;
+        let costo = data
+            .iter()
+            .zip(labels.iter())
+            .map(|(point, &cluster)| Ok(self.metrica.distance(point, &centroids[cluster])?.powi(2)))
+            .sum::<Result<f64, MlError>>()?;
+
+        Ok(KMeansAjuste {
+            centroids,
+            labels,
+            inertia,
+            costo,
+            n_iter,
+        })
+    }
+
+    /// Assigns each row of `data` to its nearest fitted centroid
+    pub fn predict(&self, data: &[Vec<f64>]) -> Result<Vec<usize>, MlError> {
+        asignar_a_clusters(data, &self.ajuste()?.centroids, &self.metrica)
+    }
+
+    /// Distance from each row of `data` to every fitted centroid
+    ///
+    /// # Returns:
+    /// - Ok(Vec<Vec<f64>>) - One row per point with `k` distances, in centroid order
+    pub fn transform(&self, data: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, MlError> {
+        distancias_a_centros(data, &self.ajuste()?.centroids, &self.metrica)
+    }
+
+    pub fn is_fitted(&self) -> bool {
+        self.ajuste.is_some()
+    }
+
+    /// Final centroids, one per cluster
+    pub fn centroids(&self) -> Result<&[Vec<f64>], MlError> {
+        Ok(&self.ajuste()?.centroids)
+    }
+
+    /// Cluster index of each row passed to `fit`
+    pub fn labels(&self) -> Result<&[usize], MlError> {
+        Ok(&self.ajuste()?.labels)
+    }
+
+    /// Total inertia (WCSS) of the training data
+    ///
+    /// Inertia is Euclidean-only: it always uses squared Euclidean distance, whatever
+    /// `metrica` is. With another metric it is not the cost `fit` minimizes.
+    pub fn inertia(&self) -> Result<f64, MlError> {
+        Ok(self.ajuste()?.inertia)
+    }
+
+    /// Number of Lloyd iterations of the run kept by `fit`
+    pub fn n_iter(&self) -> Result<usize, MlError> {
+        Ok(self.ajuste()?.n_iter)
+    }
+
+    fn ajuste(&self) -> Result<&KMeansAjuste, MlError> {
+        self.ajuste.as_ref().ok_or(MlError::NotFitted)
+    }
 }
 
 ///Asigna centroides a labels o categorias
@@ -352,3 +1316,211 @@ pub fn show_cluster_stats(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::create_dataset::filas_de_prueba;
+    use crate::modules::distancias::{Chebyshev, Cosine, Manhattan, Minkowski};
+
+    // Every cluster with points must end up at the mean of its members
+    fn centroides_son_medias(data: &[Vec<f64>], centroides: &[Vec<f64>], metrica: &dyn Distance) {
+        let labels = asignar_a_clusters(data, centroides, metrica).unwrap();
+        let (medias, counts) = medias_por_cluster(data, &labels, centroides.len());
+        for c in 0..centroides.len() {
+            if counts[c] > 0 {
+                for (a, b) in centroides[c].iter().zip(medias[c].iter()) {
+                    assert!(
+                        (a - b).abs() < 1e-9,
+                        "cluster {}: {:?} != {:?}",
+                        c,
+                        centroides[c],
+                        medias[c]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn farthest_point_recalcula_el_cluster_donante() {
+        let data = vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.0],
+            vec![10.0, 0.0],
+            vec![20.0, 0.0],
+            vec![24.0, 0.0],
+        ];
+        // Clusters 2 and 3 are empty
+        let clusters = vec![0, 0, 0, 1, 1];
+        let previos = vec![
+            vec![0.0, 0.0],
+            vec![20.0, 0.0],
+            vec![50.0, 0.0],
+            vec![60.0, 0.0],
+        ];
+
+        let nuevos = actualizar_centroides(
+            &data,
+            &clusters,
+            &previos,
+            EmptyClusterStrategy::FarthestPoint,
+            &Euclidean,
+            Verbosity::Silent,
+        )
+        .unwrap();
+
+        // 10 leaves cluster 0 (mean 3.67 -> 0.5); measured against the refreshed mean,
+        // 20 (2 away from 22) is then farther than 0 (0.5 away) and moves to cluster 3
+        assert_eq!(nuevos[0], vec![0.5, 0.0]);
+        assert_eq!(nuevos[1], vec![24.0, 0.0]);
+        assert_eq!(nuevos[2], vec![10.0, 0.0]);
+        assert_eq!(nuevos[3], vec![20.0, 0.0]);
+        centroides_son_medias(&data, &nuevos, &Euclidean);
+    }
+
+    fn ajustar<D: Distance>(
+        data: &[Vec<f64>],
+        k: usize,
+        estrategia: EmptyClusterStrategy,
+        algoritmo: KMeansAlgorithm,
+        metrica: D,
+    ) -> KMeans<D> {
+        let mut kmeans = KMeans::new(k)
+            .with_init(KMeansInit::RandomPoints)
+            .with_empty_cluster_strategy(estrategia)
+            .with_algorithm(algoritmo)
+            .with_n_init(3)
+            .with_seed(Some(17))
+            .with_metric(metrica);
+        kmeans.fit(data).unwrap();
+        kmeans
+    }
+
+    fn mismos_resultados<D: Distance>(lloyd: &KMeans<D>, elkan: &KMeans<D>) {
+        assert_eq!(lloyd.centroids().unwrap(), elkan.centroids().unwrap());
+        assert_eq!(lloyd.labels().unwrap(), elkan.labels().unwrap());
+        assert_eq!(lloyd.n_iter().unwrap(), elkan.n_iter().unwrap());
+    }
+
+    #[test]
+    fn elkan_y_lloyd_dan_el_mismo_resultado() {
+        let estrategias = [
+            EmptyClusterStrategy::KeepPrevious,
+            EmptyClusterStrategy::FarthestPoint,
+            EmptyClusterStrategy::SplitLargest,
+        ];
+        for dim in [1, 2, 5, 10] {
+            for enteros in [false, true] {
+                // With ties, identical initial centroids leave clusters empty
+                let data = filas_de_prueba(200, dim, enteros, dim as u64);
+                for k in [1, 3, 8, 20] {
+                    for estrategia in estrategias {
+                        let lloyd =
+                            ajustar(&data, k, estrategia, KMeansAlgorithm::Lloyd, Euclidean);
+                        let elkan =
+                            ajustar(&data, k, estrategia, KMeansAlgorithm::Elkan, Euclidean);
+                        mismos_resultados(&lloyd, &elkan);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn elkan_y_lloyd_coinciden_con_otras_metricas() {
+        let data = filas_de_prueba(200, 3, false, 9);
+        for estrategia in [
+            EmptyClusterStrategy::KeepPrevious,
+            EmptyClusterStrategy::SplitLargest,
+        ] {
+            let lloyd = ajustar(&data, 8, estrategia, KMeansAlgorithm::Lloyd, Manhattan);
+            let elkan = ajustar(&data, 8, estrategia, KMeansAlgorithm::Elkan, Manhattan);
+            mismos_resultados(&lloyd, &elkan);
+
+            let lloyd = ajustar(&data, 8, estrategia, KMeansAlgorithm::Lloyd, Chebyshev);
+            let elkan = ajustar(&data, 8, estrategia, KMeansAlgorithm::Elkan, Chebyshev);
+            mismos_resultados(&lloyd, &elkan);
+
+            let lloyd = ajustar(
+                &data,
+                8,
+                estrategia,
+                KMeansAlgorithm::Lloyd,
+                Minkowski::new(3.0).unwrap(),
+            );
+            let elkan = ajustar(
+                &data,
+                8,
+                estrategia,
+                KMeansAlgorithm::Elkan,
+                Minkowski::new(3.0).unwrap(),
+            );
+            mismos_resultados(&lloyd, &elkan);
+        }
+    }
+
+    #[test]
+    fn elkan_usa_lloyd_sin_desigualdad_triangular() {
+        assert!(!Cosine.satisfies_triangle_inequality());
+        assert!(!Minkowski::new(0.5).unwrap().satisfies_triangle_inequality());
+
+        let data = filas_de_prueba(200, 4, false, 21);
+        let estrategia = EmptyClusterStrategy::KeepPrevious;
+        let lloyd = ajustar(&data, 6, estrategia, KMeansAlgorithm::Lloyd, Cosine);
+        let elkan = ajustar(&data, 6, estrategia, KMeansAlgorithm::Elkan, Cosine);
+        mismos_resultados(&lloyd, &elkan);
+
+        let lloyd = ajustar(
+            &data,
+            6,
+            estrategia,
+            KMeansAlgorithm::Lloyd,
+            Minkowski::new(0.5).unwrap(),
+        );
+        let elkan = ajustar(
+            &data,
+            6,
+            estrategia,
+            KMeansAlgorithm::Elkan,
+            Minkowski::new(0.5).unwrap(),
+        );
+        mismos_resultados(&lloyd, &elkan);
+    }
+
+    // Reference for the `parallel` branch: the same per-point function, run sequentially
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn asignar_a_clusters_en_paralelo_coincide_con_secuencial() {
+        for (dim, enteros) in [(1, true), (2, false), (3, true), (8, false)] {
+            let data = filas_de_prueba(5000, dim, enteros, 40 + dim as u64);
+            for k in [1, 4, 25] {
+                let centroides = inicializar_centroides(
+                    &data,
+                    k,
+                    &KMeansInit::RandomPoints,
+                    &Euclidean,
+                    Some(k as u64),
+                    Verbosity::Silent,
+                )
+                .unwrap();
+                let secuencial = data
+                    .iter()
+                    .map(|point| centroide_mas_cercano(point, &centroides, &Manhattan))
+                    .collect::<Result<Vec<usize>, MlError>>()
+                    .unwrap();
+                assert_eq!(
+                    asignar_a_clusters(&data, &centroides, &Manhattan).unwrap(),
+                    secuencial
+                );
+            }
+        }
+
+        let mut data = filas_de_prueba(1000, 2, false, 1);
+        data[700] = vec![0.0; 3];
+        assert!(matches!(
+            asignar_a_clusters(&data, &[vec![0.0, 0.0]], &Euclidean),
+            Err(MlError::DimensionMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+use super::create_dataset::crear_rng;
+use super::distancias::{Distance, Euclidean};
+use super::error::{check_dimensions, MlError};
+use super::inercia::calcular_inercia;
+use super::kmeans::{
+    asignar_a_clusters, centroide_mas_cercano, distancias_a_centros, impl_with_metric,
+    inicializar_centroides, validar_datos, validar_tolerancia, KMeansInit, TOLERANCIA_POR_DEFECTO,
+};
+use super::verbosidad::Verbosity;
+use rand::{seq::index, Rng};
+
+/// State learned by `fit` / `partial_fit`
+#[derive(Debug, Clone)]
+struct EstadoMiniBatch {
+    centroids: Vec<Vec<f64>>,
+    /// Points seen by each centroid so far; its learning rate is 1 / count
+    counts: Vec<usize>,
+    n_iter: usize,
+}
+
+/// Mini-batch KMeans: updates the centroids from small random batches instead of the
+/// whole dataset, so each iteration costs O(batch_size * k) instead of O(n * k)
+///
+/// # Algorithm Overview:
+/// 1. Initializes centroids with `init` on a random sample of the data
+/// 2. Repeating until convergence or max iterations:
+///    a. Draws `batch_size` points at random (without replacement within the batch)
+///    b. Assigns each point of the batch to its nearest centroid
+///    c. Moves each centroid towards its points with learning rate 1 / (points it has seen)
+/// 3. Centroids settle as they see more data, so later batches move them less
+///
+/// # Example:
+/// ```ignore
+/// let mut modelo = MiniBatchKMeans::new(8).with_batch_size(1024).with_seed(Some(42));
+/// for lote in lotes_del_log {
+///     modelo.partial_fit(&lote)?;
+/// }
+/// let clusters = modelo.predict(&nuevos_eventos)?;
+/// ```
+///
+/// # Notes:
+/// - The result is close to, but usually slightly worse than, full `KMeans`
+/// - Centroids are moved as means, so non-Euclidean metrics only change assignment
+/// - `partial_fit` also fits the model, so either one enables the fitted attributes
+#[derive(Debug, Clone)]
+pub struct MiniBatchKMeans<D: Distance = Euclidean> {
+    /// Number of clusters
+    pub k: usize,
+    /// Points per batch
+    pub batch_size: usize,
+    /// Maximum number of batches processed by `fit`
+    pub max_iters: usize,
+    /// `fit` stops when no centroid coordinate moves more than this in one batch
+    pub tolerance: f64,
+    /// Initialization strategy, applied to a sample of `3 * batch_size` points
+    pub init: KMeansInit,
+    /// Seed for initialization and batch sampling, `None` uses system entropy
+    pub random_seed: Option<u64>,
+    /// Metric used for initialization, assignment and `transform`
+    pub metrica: D,
+    /// `Verbose` prints initialization and convergence messages
+    pub verbosity: Verbosity,
+    estado: Option<EstadoMiniBatch>,
+}
+
+impl MiniBatchKMeans<Euclidean> {
+    /// Euclidean mini-batch KMeans with batches of 1024, 100 max iterations,
+    /// tolerance 1e-6, KMeans++ and no seed
+    pub fn new(k: usize) -> Self {
+        MiniBatchKMeans {
+            k,
+            batch_size: 1024,
+            max_iters: 100,
+            tolerance: TOLERANCIA_POR_DEFECTO,
+            init: KMeansInit::default(),
+            random_seed: None,
+            metrica: Euclidean,
+            verbosity: Verbosity::Silent,
+            estado: None,
+        }
+    }
+}
+
+impl_with_metric!(
+    MiniBatchKMeans {
+        k,
+        batch_size,
+        max_iters,
+        tolerance,
+        init,
+        random_seed,
+        verbosity,
+    },
+    estado
+);
+
+impl<D: Distance> MiniBatchKMeans<D> {
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn with_max_iters(mut self, max_iters: usize) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_init(mut self, init: KMeansInit) -> Self {
+        self.init = init;
+        self
+    }
+
+    pub fn with_seed(mut self, random_seed: Option<u64>) -> Self {
+        self.random_seed = random_seed;
+        self
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Fits the model from scratch on random batches of `data`
+    ///
+    /// # Returns:
+    /// - Ok(()) - The model is fitted
+    /// - Err(MlError::EmptyDataset) - If `data` has no rows
+    /// - Err(MlError::InvalidInput) - If `k` or `batch_size` is 0, `k` is larger than the
+    ///   number of rows, or `tolerance` is negative or NaN
+    /// - Err(MlError::DimensionMismatch) - If the rows do not all have the same number of features
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> Result<(), MlError> {
+        self.validar(data)?;
+
+        let mut rng = crear_rng(self.random_seed);
+        // The initialization gets its own seed, drawn from `rng`, so its random stream
+        // does not repeat the one used to sample the rows
+        let semilla_inicial = Some(rng.gen::<u64>());
+
+        // Initialize on a sample so the seeding does not cost a pass over all the data
+        let n_muestra = (3 * self.batch_size).max(self.k).min(data.len());
+        let muestra: Vec<Vec<f64>> = index::sample(&mut rng, data.len(), n_muestra)
+            .into_iter()
+            .map(|i| data[i].clone())
+            .collect();
+        let centroids = inicializar_centroides(
+            &muestra,
+            self.k,
+            &self.init,
+            &self.metrica,
+            semilla_inicial,
+            self.verbosity,
+        )?;
+        let mut estado = EstadoMiniBatch {
+            counts: vec![0; centroids.len()],
+            centroids,
+            n_iter: 0,
+        };
+
+        let n_lote = self.batch_size.min(data.len());
+        let mut convergido = false;
+        while estado.n_iter < self.max_iters {
+            let lote: Vec<&[f64]> = index::sample(&mut rng, data.len(), n_lote)
+                .into_iter()
+                .map(|i| data[i].as_slice())
+                .collect();
+            let desplazamiento = actualizar_con_lote(&mut estado, &lote, &self.metrica)?;
+            estado.n_iter += 1;
+
+            if desplazamiento <= self.tolerance {
+                convergido = true;
+                if self.verbosity.is_verbose() {
+                    println!("MiniBatchKMeans converged after {} batches", estado.n_iter);
+                }
+                break;
+            }
+        }
+
+        if !convergido && self.verbosity.is_verbose() {
+            println!(
+                "MiniBatchKMeans reached maximum iterations ({}) without converging",
+                self.max_iters
+            );
+        }
+
+        self.estado = Some(estado);
+        Ok(())
+    }
+
+    /// Updates the model with one batch, e.g. a chunk read from a file or a stream
+    ///
+    /// The first call initializes the centroids from `batch`, so it must have at least
+    /// `k` rows. Every call counts as one iteration.
+    ///
+    /// # Returns:
+    /// - Ok(()) - The centroids were updated
+    /// - Err(MlError::EmptyDataset) - If `batch` has no rows
+    /// - Err(MlError::InvalidInput) - If `k` is 0 or the first batch has fewer than `k` rows
+    /// - Err(MlError::DimensionMismatch) - If a row has a different number of features
+    ///   than the centroids
+    pub fn partial_fit(&mut self, batch: &[Vec<f64>]) -> Result<(), MlError> {
+        if batch.is_empty() {
+            return Err(MlError::EmptyDataset);
+        }
+
+        let mut estado = match self.estado.take() {
+            Some(estado) => estado,
+            None => {
+                self.validar(batch)?;
+                let centroids = inicializar_centroides(
+                    batch,
+                    self.k,
+                    &self.init,
+                    &self.metrica,
+                    self.random_seed,
+                    self.verbosity,
+                )?;
+                EstadoMiniBatch {
+                    counts: vec![0; centroids.len()],
+                    centroids,
+                    n_iter: 0,
+                }
+            }
+        };
+
+        // A failed batch leaves the previous state untouched
+        let mut actualizado = estado.clone();
+        let lote: Vec<&[f64]> = batch.iter().map(|row| row.as_slice()).collect();
+        let resultado = actualizar_con_lote(&mut actualizado, &lote, &self.metrica);
+        if resultado.is_ok() {
+            actualizado.n_iter += 1;
+            estado = actualizado;
+        }
+        self.estado = Some(estado);
+
+        resultado.map(|_| ())
+    }
+
+    /// Assigns each row of `data` to its nearest fitted centroid
+    pub fn predict(&self, data: &[Vec<f64>]) -> Result<Vec<usize>, MlError> {
+        asignar_a_clusters(data, &self.estado()?.centroids, &self.metrica)
+    }
+
+    /// Distance from each row of `data` to every fitted centroid
+    pub fn transform(&self, data: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, MlError> {
+        distancias_a_centros(data, &self.estado()?.centroids, &self.metrica)
+    }
+
+    /// Total inertia (WCSS) of `data` against the fitted centroids
+    ///
+    /// Mini-batch fitting never sees the whole dataset at once, so the inertia is
+    /// computed on demand for whatever data is passed in.
+    pub fn inertia(&self, data: &[Vec<f64>]) -> Result<f64, MlError> {
+        let centroids = &self.estado()?.centroids;
+        let clusters = asignar_a_clusters(data, centroids, &self.metrica)?;
+        Ok(calcular_inercia(data, centroids, &clusters)?.total)
+    }
+
+    pub fn is_fitted(&self) -> bool {
+        self.estado.is_some()
+    }
+
+    /// Current centroids, one per cluster
+    pub fn centroids(&self) -> Result<&[Vec<f64>], MlError> {
+        Ok(&self.estado()?.centroids)
+    }
+
+    /// Number of points each centroid has absorbed
+    pub fn counts(&self) -> Result<&[usize], MlError> {
+        Ok(&self.estado()?.counts)
+    }
+
+    /// Number of batches processed
+    pub fn n_iter(&self) -> Result<usize, MlError> {
+        Ok(self.estado()?.n_iter)
+    }
+
+    fn validar(&self, data: &[Vec<f64>]) -> Result<(), MlError> {
+        if self.batch_size == 0 {
+            return Err(MlError::InvalidInput(
+                "batch_size debe ser mayor a 0".to_string(),
+            ));
+        }
+        validar_tolerancia(self.tolerance)?;
+        validar_datos(data, self.k)
+    }
+
+    fn estado(&self) -> Result<&EstadoMiniBatch, MlError> {
+        self.estado.as_ref().ok_or(MlError::NotFitted)
+    }
+}
+
+/// Moves the centroids towards the points of one batch
+///
+/// # Returns:
+/// - Ok(f64) - Largest change of any centroid coordinate during the batch
+/// - Err(MlError) - If a point and the centroids have a different number of features
+fn actualizar_con_lote(
+    estado: &mut EstadoMiniBatch,
+    lote: &[&[f64]],
+    metrica: &dyn Distance,
+) -> Result<f64, MlError> {
+    let anteriores = estado.centroids.clone();
+
+    // Assign the whole batch first so every point sees the same centroids
+    let clusters = lote
+        .iter()
+        .map(|point| centroide_mas_cercano(point, &anteriores, metrica))
+        .collect::<Result<Vec<usize>, MlError>>()?;
+
+    for (point, &c) in lote.iter().zip(clusters.iter()) {
+        check_dimensions(&estado.centroids[c], point)?;
+        estado.counts[c] += 1;
+        let eta = 1.0 / estado.counts[c] as f64;
+        for (valor, &x) in estado.centroids[c].iter_mut().zip(point.iter()) {
+            *valor += eta * (x - *valor);
+        }
+    }
+
+    let mut desplazamiento: f64 = 0.0;
+    for (anterior, nuevo) in anteriores.iter().zip(estado.centroids.iter()) {
+        for (a, b) in anterior.iter().zip(nuevo.iter()) {
+            desplazamiento = desplazamiento.max((a - b).abs());
+        }
+    }
+
+    Ok(desplazamiento)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // Three well separated blobs, rows shuffled by the rng
+    fn blobs(n: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut rng = crear_rng(Some(seed));
+        let centros = [[0.0, 0.0], [10.0, 10.0], [0.0, 20.0]];
+        (0..n)
+            .map(|_| {
+                let centro = centros[rng.gen_range(0..centros.len())];
+                centro
+                    .iter()
+                    .map(|&c| c + rng.gen_range(-2.0..2.0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partial_fit_por_bloques_baja_la_inercia() {
+        let data = blobs(1000, 3);
+        let bloques: Vec<&[Vec<f64>]> = data.chunks(100).collect();
+
+        let mut modelo = MiniBatchKMeans::new(3)
+            .with_init(KMeansInit::RandomPoints)
+            .with_seed(Some(5));
+
+        // The first partial_fit starts from these centroids
+        let iniciales = inicializar_centroides(
+            bloques[0],
+            3,
+            &KMeansInit::RandomPoints,
+            &Euclidean,
+            Some(5),
+            Verbosity::Silent,
+        )
+        .unwrap();
+        let clusters = asignar_a_clusters(&data, &iniciales, &Euclidean).unwrap();
+        let inercia_inicial = calcular_inercia(&data, &iniciales, &clusters)
+            .unwrap()
+            .total;
+
+        for bloque in &bloques {
+            modelo.partial_fit(bloque).unwrap();
+        }
+
+        assert_eq!(modelo.n_iter().unwrap(), bloques.len());
+        assert_eq!(modelo.counts().unwrap().iter().sum::<usize>(), data.len());
+        let inercia_final = modelo.inertia(&data).unwrap();
+        assert!(
+            inercia_final < inercia_inicial,
+            "inercia final {} no es menor que la inicial {}",
+            inercia_final,
+            inercia_inicial
+        );
+    }
+}
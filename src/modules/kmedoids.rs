@@ -0,0 +1,325 @@
+use super::distancias::{Distance, Euclidean};
+use super::error::MlError;
+use super::kmeans::{asignar_a_clusters, distancias_a_centros, impl_with_metric, validar_datos};
+use super::verbosidad::Verbosity;
+
+/// State learned by `KMedoids::fit`
+#[derive(Debug, Clone)]
+struct KMedoidsAjuste {
+    medoid_indices: Vec<usize>,
+    medoids: Vec<Vec<f64>>,
+    labels: Vec<usize>,
+    cost: f64,
+    n_iter: usize,
+}
+
+/// K-medoids clustering with PAM (Partitioning Around Medoids)
+///
+/// # Algorithm Overview:
+/// Like KMeans, but every cluster center (medoid) is one of the data points and the
+/// objective is the sum of distances (not squared) from each point to its medoid, so it
+/// works with any metric from `distancias` and is less sensitive to outliers.
+/// 1. BUILD: the first medoid is the point with the smallest total distance to all the
+///    others; each next medoid is the point that lowers the total cost the most
+/// 2. SWAP: repeatedly applies the (medoid, non-medoid) exchange that lowers the total cost
+///    the most, until no exchange improves it or `max_iters` swaps were made
+///
+/// # Example:
+/// ```ignore
+/// let mut kmedoids = KMedoids::new(3).with_metric(Manhattan);
+/// kmedoids.fit(&dataset.rows)?;
+/// for &i in kmedoids.medoid_indices()? {
+///     println!("Medoide: fila {} ({}) {:?}", i, dataset.class_labels[i], dataset.rows[i]);
+/// }
+/// ```
+///
+/// # Notes:
+/// - Deterministic: there is no random initialization
+/// - Precomputes all pairwise distances, so it needs O(n²) memory and each swap step
+///   costs O(k * n²); use it for thousands of rows, not millions
+/// - Assumes a symmetric metric with finite distances
+#[derive(Debug, Clone)]
+pub struct KMedoids<D: Distance = Euclidean> {
+    /// Number of clusters
+    pub k: usize,
+    /// Maximum number of swaps
+    pub max_iters: usize,
+    /// Metric used for the cost, assignment and `transform`
+    pub metrica: D,
+    /// `Verbose` prints the BUILD cost and every swap
+    pub verbosity: Verbosity,
+    ajuste: Option<KMedoidsAjuste>,
+}
+
+impl KMedoids<Euclidean> {
+    /// Euclidean K-medoids with at most 300 swaps
+    pub fn new(k: usize) -> Self {
+        KMedoids {
+            k,
+            max_iters: 300,
+            metrica: Euclidean,
+            verbosity: Verbosity::Silent,
+            ajuste: None,
+        }
+    }
+}
+
+impl_with_metric!(
+    KMedoids {
+        k,
+        max_iters,
+        verbosity
+    },
+    ajuste
+);
+
+impl<D: Distance> KMedoids<D> {
+    pub fn with_max_iters(mut self, max_iters: usize) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Runs PAM on `data` and stores medoids, labels, cost and number of swaps
+    ///
+    /// # Returns:
+    /// - Ok(()) - The model is fitted
+    /// - Err(MlError::EmptyDataset) - If `data` has no rows
+    /// - Err(MlError::InvalidInput) - If `k` is 0 or larger than the number of rows
+    /// - Err(MlError::DimensionMismatch) - If the rows do not all have the same number of features
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> Result<(), MlError> {
+        validar_datos(data, self.k)?;
+
+        let distancias = matriz_distancias(data, &self.metrica)?;
+        let mut medoides = construir_medoides(&distancias, self.k);
+        let mut cercanias = Cercanias::calcular(&distancias, &medoides);
+
+        if self.verbosity.is_verbose() {
+            println!(
+                "KMedoids BUILD: medoides {:?}, costo {:.4}",
+                medoides,
+                cercanias.costo()
+            );
+        }
+
+        let mut n_iter = 0;
+        while n_iter < self.max_iters {
+            let Some((posicion, candidato, delta)) =
+                mejor_intercambio(&distancias, &medoides, &cercanias)
+            else {
+                break;
+            };
+
+            medoides[posicion] = candidato;
+            cercanias = Cercanias::calcular(&distancias, &medoides);
+            n_iter += 1;
+
+            if self.verbosity.is_verbose() {
+                println!(
+                    "KMedoids SWAP {}: medoide {} -> punto {} (delta {:.4}), costo {:.4}",
+                    n_iter,
+                    posicion,
+                    candidato,
+                    delta,
+                    cercanias.costo()
+                );
+            }
+        }
+
+        if n_iter == self.max_iters && self.verbosity.is_verbose() {
+            println!(
+                "KMedoids reached maximum iterations ({}) without converging",
+                self.max_iters
+            );
+        }
+
+        self.ajuste = Some(KMedoidsAjuste {
+            medoids: medoides.iter().map(|&i| data[i].clone()).collect(),
+            labels: cercanias.cluster,
+            cost: cercanias.distancia.iter().sum(),
+            medoid_indices: medoides,
+            n_iter,
+        });
+        Ok(())
+    }
+
+    /// Assigns each row of `data` to its nearest medoid
+    pub fn predict(&self, data: &[Vec<f64>]) -> Result<Vec<usize>, MlError> {
+        asignar_a_clusters(data, &self.ajuste()?.medoids, &self.metrica)
+    }
+
+    /// Distance from each row of `data` to every medoid
+    pub fn transform(&self, data: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, MlError> {
+        distancias_a_centros(data, &self.ajuste()?.medoids, &self.metrica)
+    }
+
+    pub fn is_fitted(&self) -> bool {
+        self.ajuste.is_some()
+    }
+
+    /// Row index in the training data of each medoid, in cluster order
+    pub fn medoid_indices(&self) -> Result<&[usize], MlError> {
+        Ok(&self.ajuste()?.medoid_indices)
+    }
+
+    /// Copy of the training row of each medoid, in cluster order
+    pub fn medoids(&self) -> Result<&[Vec<f64>], MlError> {
+        Ok(&self.ajuste()?.medoids)
+    }
+
+    /// Cluster index of each row passed to `fit`
+    pub fn labels(&self) -> Result<&[usize], MlError> {
+        Ok(&self.ajuste()?.labels)
+    }
+
+    /// Sum of the distances from each training row to its medoid
+    pub fn cost(&self) -> Result<f64, MlError> {
+        Ok(self.ajuste()?.cost)
+    }
+
+    /// Number of swaps made by `fit`
+    pub fn n_iter(&self) -> Result<usize, MlError> {
+        Ok(self.ajuste()?.n_iter)
+    }
+
+    fn ajuste(&self) -> Result<&KMedoidsAjuste, MlError> {
+        self.ajuste.as_ref().ok_or(MlError::NotFitted)
+    }
+}
+
+/// Nearest and second-nearest medoid of every point
+struct Cercanias {
+    /// Position (cluster index) of the nearest medoid
+    cluster: Vec<usize>,
+    /// Distance to the nearest medoid
+    distancia: Vec<f64>,
+    /// Distance to the second-nearest medoid, infinity if k = 1
+    segunda: Vec<f64>,
+}
+
+impl Cercanias {
+    fn calcular(distancias: &[Vec<f64>], medoides: &[usize]) -> Self {
+        let n = distancias.len();
+        let mut cercanias = Cercanias {
+            cluster: vec![0; n],
+            distancia: vec![f64::INFINITY; n],
+            segunda: vec![f64::INFINITY; n],
+        };
+
+        for (j, fila) in distancias.iter().enumerate() {
+            // Strict comparison: the first medoid wins ties, like `asignar_a_clusters`
+            for (posicion, &m) in medoides.iter().enumerate() {
+                let d = fila[m];
+                if d < cercanias.distancia[j] {
+                    cercanias.segunda[j] = cercanias.distancia[j];
+                    cercanias.distancia[j] = d;
+                    cercanias.cluster[j] = posicion;
+                } else if d < cercanias.segunda[j] {
+                    cercanias.segunda[j] = d;
+                }
+            }
+        }
+
+        cercanias
+    }
+
+    fn costo(&self) -> f64 {
+        self.distancia.iter().sum()
+    }
+}
+
+/// All pairwise distances between the rows of `data`
+fn matriz_distancias(data: &[Vec<f64>], metrica: &dyn Distance) -> Result<Vec<Vec<f64>>, MlError> {
+    let n = data.len();
+    let mut distancias = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d = metrica.distance(&data[i], &data[j])?;
+            distancias[i][j] = d;
+            distancias[j][i] = d;
+        }
+    }
+    Ok(distancias)
+}
+
+/// BUILD phase: greedily picks the `k` points that lower the total cost the most
+fn construir_medoides(distancias: &[Vec<f64>], k: usize) -> Vec<usize> {
+    let n = distancias.len();
+    let mut medoides = Vec::with_capacity(k);
+    let mut es_medoide = vec![false; n];
+    let mut mas_cercana = vec![f64::INFINITY; n];
+
+    while medoides.len() < k {
+        // Gain of adding candidate i: how much it shortens each point's nearest distance
+        let mut mejor: Option<(usize, f64)> = None;
+        for i in (0..n).filter(|&i| !es_medoide[i]) {
+            let ganancia: f64 = (0..n)
+                .map(|j| {
+                    if mas_cercana[j].is_infinite() {
+                        // First medoid: gain is minus the total distance to i
+                        -distancias[j][i]
+                    } else {
+                        (mas_cercana[j] - distancias[j][i]).max(0.0)
+                    }
+                })
+                .sum();
+            if mejor.is_none_or(|(_, max)| ganancia > max) {
+                mejor = Some((i, ganancia));
+            }
+        }
+
+        let Some((elegido, _)) = mejor else {
+            break;
+        };
+        medoides.push(elegido);
+        es_medoide[elegido] = true;
+        for j in 0..n {
+            mas_cercana[j] = mas_cercana[j].min(distancias[j][elegido]);
+        }
+    }
+
+    medoides
+}
+
+/// SWAP phase: the exchange of a medoid for a non-medoid that lowers the cost the most
+///
+/// # Returns:
+/// - Some((posicion, candidato, delta)) - Replace `medoides[posicion]` with point
+///   `candidato` to change the cost by `delta` (< 0)
+/// - None - If no exchange lowers the cost
+fn mejor_intercambio(
+    distancias: &[Vec<f64>],
+    medoides: &[usize],
+    cercanias: &Cercanias,
+) -> Option<(usize, usize, f64)> {
+    let n = distancias.len();
+    let costo = cercanias.costo();
+    let mut mejor: Option<(usize, usize, f64)> = None;
+
+    for candidato in (0..n).filter(|h| !medoides.contains(h)) {
+        for posicion in 0..medoides.len() {
+            let delta: f64 = (0..n)
+                .map(|j| {
+                    let al_candidato = distancias[j][candidato];
+                    if cercanias.cluster[j] == posicion {
+                        // j loses its medoid: goes to the candidate or its second nearest
+                        al_candidato.min(cercanias.segunda[j]) - cercanias.distancia[j]
+                    } else {
+                        (al_candidato - cercanias.distancia[j]).min(0.0)
+                    }
+                })
+                .sum();
+
+            if mejor.is_none_or(|(_, _, min)| delta < min) {
+                mejor = Some((posicion, candidato, delta));
+            }
+        }
+    }
+
+    // Ignore improvements that are only floating-point noise, or PAM may cycle
+    mejor.filter(|&(_, _, delta)| delta < -1e-12 * costo.max(1.0))
+}
@@ -1,163 +1,523 @@
 use super::dataset_struct::Dataset;
-use super::distancias::euclidean_distance;
+use super::distancias::{comparar_distancias, Distance, Euclidean};
+use super::error::MlError;
+use super::kd_tree::KdTree;
+use super::verbosidad::Verbosity;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::{fmt::format, vec};
 
+/// Vecino de KNN: (indice de entrenamiento, distancia, clase)
+pub type Vecino = (usize, f64, String);
+
 pub fn matrix_euclidean(
     target_dataset: &Dataset,
     training_dataset: &Dataset,
     k: usize,
-) -> Result<Vec<Vec<(usize, f64, String)>>, String> {
+) -> Result<Vec<Vec<Vecino>>, MlError> {
+    matrix_distancias(target_dataset, training_dataset, k, &Euclidean)
+}
+
+/// Igual que `matrix_euclidean` pero con cualquier metrica de `distancias`
+pub fn matrix_distancias(
+    target_dataset: &Dataset,
+    training_dataset: &Dataset,
+    k: usize,
+    metrica: &dyn Distance,
+) -> Result<Vec<Vec<Vecino>>, MlError> {
     //Verificar el numero de features de cada dataset(training,testing)
     if !target_dataset.rows.is_empty() && !training_dataset.rows.is_empty() {
         let target_features = target_dataset.rows[0].len();
         let training_features = training_dataset.rows[0].len();
 
         if target_features != training_features {
-            return Err(format!(
+            return Err(MlError::InvalidInput(format!(
                 "Conteo de features no coinciden: dataset de testing tiene {} features, dataset de entrenamiento tiene {}features",
                 target_features,training_features
-            ));
+            )));
         }
     }
 
     //*Verificar que dataset de entrenamiento tiene labels de clase por cada muestra */
     if training_dataset.rows.len() != training_dataset.class_labels.len() {
-        return Err(format!(
+        return Err(MlError::InvalidInput(format!(
             "Training dataset has {} samples but {} class labels",
             training_dataset.rows.len(),
             training_dataset.class_labels.len()
-        ));
+        )));
     }
 
-    let mut all_nearest_neighbors = Vec::new();
-
     //*Por cada muestra del dataset objetivo o de testeo */
-    for (i, target) in target_dataset.rows.iter().enumerate() {
-        //Calcula la distancia a todas las muestras de entrenamiento
-
-        let mut distancias: Vec<(usize, f64, String)> = training_dataset
-            .rows
-            .iter()
-            .enumerate()
-            .map(|(j, row)| {
-                let distancia = euclidean_distance(target, row);
-                let class = training_dataset.class_labels[j].clone();
-                (j, distancia, class)
-            })
-            .collect();
-
-        //Sorting
-        distancias.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-
-        if !distancias.is_empty() {
-            let target_features = target
-                .iter()
-                .map(|x| format!("{:.2}", x))
-                .collect::<Vec<String>>()
-                .join(", ");
+    let vecinos_de = |target: &Vec<f64>| -> Result<Vec<Vecino>, MlError> {
+        //Calcula la distancia a todas las muestras de entrenamiento y conserva las k mas cercanas
+        Ok(
+            k_nearest_indices(&training_dataset.rows, target, k, metrica)?
+                .into_iter()
+                .map(|(j, distancia)| (j, distancia, training_dataset.class_labels[j].clone()))
+                .collect(),
+        )
+    };
 
-            // Mostrar los datos de la muestra más cercana
-            let closest_sample = training_dataset.rows[distancias[0].0]
-                .iter()
-                .map(|x| format!("{:.2}", x))
-                .collect::<Vec<String>>()
-                .join(", ");
-
-            println!(
-                "Test {} [{}] más cercano: Sample número: {} [{}] con distancia {:.4}, Clase {}",
-                i,
-                target_features,
-                distancias[0].0,
-                closest_sample,
-                distancias[0].1,
-                distancias[0].2
-            );
-        }
+    // Con el feature `parallel` cada muestra se procesa en un hilo de rayon;
+    // el resultado conserva el orden del dataset objetivo
+    #[cfg(feature = "parallel")]
+    let all_nearest_neighbors = target_dataset.rows.par_iter().map(vecinos_de).collect();
+    #[cfg(not(feature = "parallel"))]
+    let all_nearest_neighbors = target_dataset.rows.iter().map(vecinos_de).collect();
+
+    all_nearest_neighbors
+}
 
-        //Truncar al neighbor mas cercano si uno existe
-        let k_actual = std::cmp::min(k, distancias.len());
-        let nearest_neighbors = distancias[0..k_actual].to_vec();
+/// Resultado de KNN para una muestra del dataset objetivo
+#[derive(Debug, Clone)]
+pub struct SampleResult {
+    /// Indice de la muestra en el dataset objetivo
+    pub index: usize,
+    /// Vecinos (indice de entrenamiento, distancia, clase), del mas cercano al mas lejano
+    pub neighbors: Vec<Vecino>,
+    pub predicted: String,
+    pub actual: String,
+}
 
-        all_nearest_neighbors.push(nearest_neighbors);
+impl SampleResult {
+    pub fn is_correct(&self) -> bool {
+        self.predicted == self.actual
     }
+}
+
+/// Resultado completo de evaluar KNN sobre un dataset
+#[derive(Debug, Clone)]
+pub struct KnnEvaluation {
+    pub accuracy: f64,
+    /// Un resultado por muestra, en el mismo orden que el dataset objetivo
+    pub samples: Vec<SampleResult>,
+}
+
+impl KnnEvaluation {
+    /// Clase predicha de cada muestra, en el orden del dataset objetivo
+    pub fn predictions(&self) -> Vec<String> {
+        self.samples.iter().map(|s| s.predicted.clone()).collect()
+    }
+}
 
-    Ok(all_nearest_neighbors)
+/// Imprime la muestra y su vecino mas cercano (lo que antes hacia `matrix_euclidean`)
+pub fn print_sample_result(sample: &SampleResult, target: &[f64], training_rows: &[Vec<f64>]) {
+    let Some(closest) = sample.neighbors.first() else {
+        return;
+    };
+
+    let target_features = target
+        .iter()
+        .map(|x| format!("{:.2}", x))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    // Mostrar los datos de la muestra más cercana
+    let closest_sample = training_rows[closest.0]
+        .iter()
+        .map(|x| format!("{:.2}", x))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    println!(
+        "Test {} [{}] más cercano: Sample número: {} [{}] con distancia {:.4}, Clase {}",
+        sample.index, target_features, closest.0, closest_sample, closest.1, closest.2
+    );
 }
 
 fn find_k_nearest(
     dataset: &Dataset,
     target: &[f64],
     k: usize,
-) -> Result<Vec<(usize, f64, String)>, String> {
+    metrica: &dyn Distance,
+) -> Result<Vec<Vecino>, MlError> {
     if k == 0 {
-        return Err("K debe ser mayor a 0".to_string());
+        return Err(MlError::InvalidInput("K debe ser mayor a 0".to_string()));
     }
     if dataset.rows.is_empty() {
-        return Err("Dataset esta vacio".to_string());
+        return Err(MlError::EmptyDataset);
     }
-    //Calcular distancias
-    let mut distancias: Vec<(usize, f64, String)> = dataset
-        .rows
+    //Calcular distancias y quedarse con las k mas cercanas
+    let distancias = k_nearest_indices(&dataset.rows, target, k, metrica)?
+        .into_iter()
+        .map(|(j, distancia)| (j, distancia, dataset.class_labels[j].clone()))
+        .collect();
+
+    Ok(distancias)
+}
+
+/// Indices y distancias de las k filas mas cercanas a `target`, de la mas cercana a la mas lejana
+///
+/// Usa seleccion parcial (`select_nth_unstable_by`) en lugar de ordenar todas
+/// las distancias: O(n + k log k) por consulta. Los empates se resuelven por
+/// indice y las distancias NaN quedan al final, asi el resultado es determinista.
+pub fn k_nearest_indices(
+    rows: &[Vec<f64>],
+    target: &[f64],
+    k: usize,
+    metrica: &dyn Distance,
+) -> Result<Vec<(usize, f64)>, MlError> {
+    let mut distancias = rows
         .iter()
         .enumerate()
-        .map(|(j, row)| {
-            let distancia = euclidean_distance(&target, &row);
-            let clase = dataset.class_labels[j].clone();
-            (j, distancia, clase)
-        })
-        .collect();
-    //Sort por mas cercano
-    distancias.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        .map(|(j, row)| Ok((j, metrica.distance(target, row)?)))
+        .collect::<Result<Vec<(usize, f64)>, MlError>>()?;
+
+    let k_actual = k.min(distancias.len());
+    if k_actual == 0 {
+        return Ok(Vec::new());
+    }
 
-    //Truncar al k mas cercano
-    distancias.truncate(k);
+    let orden =
+        |a: &(usize, f64), b: &(usize, f64)| comparar_distancias(a.1, b.1).then(a.0.cmp(&b.0));
+
+    //Seleccion parcial: los k mas cercanos quedan al inicio, sin orden
+    if k_actual < distancias.len() {
+        distancias.select_nth_unstable_by(k_actual - 1, orden);
+        distancias.truncate(k_actual);
+    }
+    distancias.sort_unstable_by(orden);
 
     Ok(distancias)
 }
-// Function to predict the class for a single sample based on its nearest neighbors
-pub fn predict_class(neighbors: &[(usize, f64, String)]) -> String {
-    // Count occurrences of each class
-    let mut class_counts: std::collections::HashMap<String, usize> =
-        std::collections::HashMap::new();
+/// How each neighbor's vote is weighted when predicting a class
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VotingStrategy {
+    /// Every neighbor counts as one vote (plain majority)
+    #[default]
+    Uniform,
+    /// Vote weight is 1 / distance; neighbors at distance 0 win outright
+    InverseDistance,
+    /// Vote weight is exp(-d² / (2 * bandwidth²)); `bandwidth` must be finite and > 0
+    Gaussian { bandwidth: f64 },
+}
 
-    for (_, _, class) in neighbors {
-        *class_counts.entry(class.clone()).or_insert(0) += 1;
+impl VotingStrategy {
+    /// Checks the strategy parameters
+    ///
+    /// # Returns
+    /// - Err(MlError::InvalidInput) - If a Gaussian bandwidth is not finite or not positive
+    pub fn validate(&self) -> Result<(), MlError> {
+        if let VotingStrategy::Gaussian { bandwidth } = self {
+            if !(bandwidth.is_finite() && *bandwidth > 0.0) {
+                return Err(MlError::InvalidInput(format!(
+                    "El bandwidth gaussiano debe ser finito y mayor a 0, se recibio {}",
+                    bandwidth
+                )));
+            }
+        }
+        Ok(())
     }
 
-    // Find the most common class
-    class_counts
+    /// Weight of a neighbor at the given distance, NaN distances get weight 0
+    pub fn weight(&self, distance: f64) -> f64 {
+        let w = match self {
+            VotingStrategy::Uniform => 1.0,
+            VotingStrategy::InverseDistance => 1.0 / distance,
+            VotingStrategy::Gaussian { bandwidth } => {
+                (-(distance * distance) / (2.0 * bandwidth * bandwidth)).exp()
+            }
+        };
+        if w.is_nan() {
+            0.0
+        } else {
+            w
+        }
+    }
+}
+
+// Function to predict the class for a single sample based on its nearest neighbors
+pub fn predict_class(neighbors: &[Vecino]) -> String {
+    clase_ganadora(neighbors, VotingStrategy::Uniform)
+}
+
+/// Predicts the class of a sample from its neighbors using the given voting strategy
+///
+/// `neighbors` must be ordered nearest first, as returned by `matrix_distancias`,
+/// `find_k_nearest` or `KdTree::k_nearest`. Ties are broken by the nearest
+/// neighbor among the tied classes, so the prediction never depends on hash
+/// map iteration order.
+///
+/// # Returns
+/// - Err(MlError::InvalidInput) - If `strategy` is not valid (see `VotingStrategy::validate`)
+pub fn predict_class_weighted(
+    neighbors: &[Vecino],
+    strategy: VotingStrategy,
+) -> Result<String, MlError> {
+    strategy.validate()?;
+    Ok(clase_ganadora(neighbors, strategy))
+}
+
+// Class with the highest vote, the nearest one on ties
+fn clase_ganadora(neighbors: &[Vecino], strategy: VotingStrategy) -> String {
+    class_votes(neighbors, strategy)
         .into_iter()
-        .max_by_key(|(_, count)| *count)
-        .map(|(class, _)| class)
+        .fold(None::<(&str, f64)>, |best, (class, score)| match best {
+            // Strict > keeps the class seen first (nearest) on ties
+            Some((_, best_score)) if score <= best_score => best,
+            _ => Some((class, score)),
+        })
+        .map(|(class, _)| class.to_string())
         .unwrap_or_else(|| "Unknown".to_string())
 }
 
+/// Vote share of every class in `classes` (same order), using the given voting strategy
+///
+/// Classes with no neighbors get 0.0 and the shares add up to 1.0. If every
+/// weight is zero (e.g. a very small Gaussian bandwidth) it falls back to uniform votes.
+///
+/// # Returns
+/// - Err(MlError::InvalidInput) - If `strategy` is not valid (see `VotingStrategy::validate`)
+pub fn predict_proba(
+    neighbors: &[Vecino],
+    classes: &[String],
+    strategy: VotingStrategy,
+) -> Result<Vec<f64>, MlError> {
+    strategy.validate()?;
+    let mut votes = class_votes(neighbors, strategy);
+    if votes.iter().map(|(_, score)| score).sum::<f64>() <= 0.0 {
+        votes = class_votes(neighbors, VotingStrategy::Uniform);
+    }
+    let total: f64 = votes.iter().map(|(_, score)| score).sum();
+
+    Ok(classes
+        .iter()
+        .map(|class| {
+            let score = votes
+                .iter()
+                .find(|(c, _)| *c == class.as_str())
+                .map_or(0.0, |(_, score)| *score);
+            if total > 0.0 {
+                score / total
+            } else {
+                0.0
+            }
+        })
+        .collect())
+}
+
+// Accumulated vote per class, in order of first appearance among the neighbors
+fn class_votes(neighbors: &[Vecino], strategy: VotingStrategy) -> Vec<(&str, f64)> {
+    // With inverse distance an exact match has infinite weight: only exact matches vote
+    let exact_matches = strategy == VotingStrategy::InverseDistance
+        && neighbors.iter().any(|(_, distance, _)| *distance == 0.0);
+
+    let mut votes: Vec<(&str, f64)> = Vec::new();
+    for (_, distance, class) in neighbors {
+        let weight = if exact_matches {
+            if *distance == 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            strategy.weight(*distance)
+        };
+
+        match votes.iter_mut().find(|(c, _)| *c == class.as_str()) {
+            Some((_, score)) => *score += weight,
+            None => votes.push((class.as_str(), weight)),
+        }
+    }
+    votes
+}
+
 // Function to evaluate KNN model on the target dataset
 pub fn evaluate_knn(
     target_dataset: &Dataset,
     training_dataset: &Dataset,
     k: usize,
-) -> Result<(f64, Vec<String>), String> {
-    let neighbors_result = matrix_euclidean(target_dataset, training_dataset, k)?;
+    metrica: &dyn Distance,
+    voting: VotingStrategy,
+    verbosity: Verbosity,
+) -> Result<KnnEvaluation, MlError> {
+    evaluate_knn_with_callback(
+        target_dataset,
+        training_dataset,
+        k,
+        metrica,
+        voting,
+        &mut |sample| {
+            if verbosity.is_verbose() {
+                print_sample_result(
+                    sample,
+                    &target_dataset.rows[sample.index],
+                    &training_dataset.rows,
+                );
+            }
+        },
+    )
+}
+
+/// Same as `evaluate_knn`, but calls `on_sample` with each sample's result as it is produced
+///
+/// Use it for logging, progress bars or streaming results somewhere else
+/// instead of printing to stdout.
+pub fn evaluate_knn_with_callback(
+    target_dataset: &Dataset,
+    training_dataset: &Dataset,
+    k: usize,
+    metrica: &dyn Distance,
+    voting: VotingStrategy,
+    on_sample: &mut dyn FnMut(&SampleResult),
+) -> Result<KnnEvaluation, MlError> {
+    voting.validate()?;
+    let neighbors_result = matrix_distancias(target_dataset, training_dataset, k, metrica)?;
+
+    score_predictions(target_dataset, neighbors_result, voting, on_sample)
+}
+
+// Same as evaluate_knn but queries a prebuilt KD-tree instead of brute force (Euclidean only)
+pub fn evaluate_knn_kdtree(
+    target_dataset: &Dataset,
+    index: &KdTree,
+    k: usize,
+    voting: VotingStrategy,
+    verbosity: Verbosity,
+) -> Result<KnnEvaluation, MlError> {
+    voting.validate()?;
+    let neighbors_result = target_dataset
+        .rows
+        .iter()
+        .map(|target| index.k_nearest(target, k))
+        .collect::<Result<Vec<_>, MlError>>()?;
+
+    score_predictions(target_dataset, neighbors_result, voting, &mut |sample| {
+        if verbosity.is_verbose() {
+            print_sample_result(sample, &target_dataset.rows[sample.index], index.rows());
+        }
+    })
+}
+
+/// Class probabilities for every sample of `target_dataset`
+///
+/// # Returns
+/// - Ok((classes, probabilities)) - `classes` are the distinct labels of the training
+///   dataset in alphabetical order; `probabilities[i][c]` is the vote share of
+///   `classes[c]` for sample `i`
+pub fn knn_predict_proba(
+    target_dataset: &Dataset,
+    training_dataset: &Dataset,
+    k: usize,
+    metrica: &dyn Distance,
+    voting: VotingStrategy,
+) -> Result<(Vec<String>, Vec<Vec<f64>>), MlError> {
+    voting.validate()?;
+    let neighbors_result = matrix_distancias(target_dataset, training_dataset, k, metrica)?;
+    let classes = training_dataset.unique_labels();
+
+    let probabilities = neighbors_result
+        .iter()
+        .map(|neighbors| predict_proba(neighbors, &classes, voting))
+        .collect::<Result<_, MlError>>()?;
+
+    Ok((classes, probabilities))
+}
 
+// Vote on each sample's neighbors and compare with the target labels
+fn score_predictions(
+    target_dataset: &Dataset,
+    neighbors_result: Vec<Vec<Vecino>>,
+    voting: VotingStrategy,
+    on_sample: &mut dyn FnMut(&SampleResult),
+) -> Result<KnnEvaluation, MlError> {
     // Only perform evaluation if target dataset has class labels
     if target_dataset.class_labels.len() != target_dataset.rows.len() {
-        return Err("Cannot evaluate: target dataset lacks class labels".to_string());
+        return Err(MlError::InvalidInput(
+            "Cannot evaluate: target dataset lacks class labels".to_string(),
+        ));
     }
 
-    let mut predictions = Vec::new();
+    let mut samples = Vec::with_capacity(neighbors_result.len());
     let mut correct_count = 0;
 
-    for (i, neighbors) in neighbors_result.iter().enumerate() {
-        let predicted_class = predict_class(neighbors);
-        predictions.push(predicted_class.clone());
+    for (i, neighbors) in neighbors_result.into_iter().enumerate() {
+        let sample = SampleResult {
+            index: i,
+            predicted: predict_class_weighted(&neighbors, voting)?,
+            actual: target_dataset.class_labels[i].clone(),
+            neighbors,
+        };
 
-        if predicted_class == target_dataset.class_labels[i] {
+        if sample.is_correct() {
             correct_count += 1;
         }
+
+        on_sample(&sample);
+        samples.push(sample);
     }
 
     let accuracy = correct_count as f64 / target_dataset.rows.len() as f64;
 
-    Ok((accuracy, predictions))
+    Ok(KnnEvaluation { accuracy, samples })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "parallel")]
+    use crate::modules::create_dataset::filas_de_prueba;
+    #[cfg(feature = "parallel")]
+    use crate::modules::distancias::Manhattan;
+
+    fn vecinos() -> Vec<Vecino> {
+        vec![
+            (0, 0.5, "A".to_string()),
+            (1, 1.0, "B".to_string()),
+            (2, 2.0, "B".to_string()),
+        ]
+    }
+
+    #[test]
+    fn bandwidth_gaussiano_invalido_es_error() {
+        let classes = vec!["A".to_string(), "B".to_string()];
+        for bandwidth in [0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let strategy = VotingStrategy::Gaussian { bandwidth };
+            assert!(matches!(strategy.validate(), Err(MlError::InvalidInput(_))));
+            assert!(matches!(
+                predict_class_weighted(&vecinos(), strategy),
+                Err(MlError::InvalidInput(_))
+            ));
+            assert!(matches!(
+                predict_proba(&vecinos(), &classes, strategy),
+                Err(MlError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn bandwidth_gaussiano_valido_pondera_por_distancia() {
+        let strategy = VotingStrategy::Gaussian { bandwidth: 0.5 };
+        assert!(strategy.validate().is_ok());
+        // Uniforme gana B (2 votos); con bandwidth chico pesa mas el vecino A a 0.5
+        assert_eq!(predict_class(&vecinos()), "B");
+        assert_eq!(predict_class_weighted(&vecinos(), strategy).unwrap(), "A");
+    }
+
+    #[cfg(feature = "parallel")]
+    fn dataset(n: usize, dim: usize, seed: u64) -> Dataset {
+        let labels = (0..n).map(|i| format!("C{}", i % 3)).collect();
+        Dataset::new(filas_de_prueba(n, dim, true, seed), Vec::new(), labels)
+    }
+
+    // `find_k_nearest` on each target row is the sequential version of `matrix_distancias`
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn matrix_distancias_en_paralelo_coincide_con_secuencial() {
+        for dim in [1, 3, 6] {
+            let training = dataset(600, dim, dim as u64);
+            let target = dataset(300, dim, 100 + dim as u64);
+            for k in [1, 5, 700] {
+                let secuencial: Vec<Vec<Vecino>> = target
+                    .rows
+                    .iter()
+                    .map(|row| find_k_nearest(&training, row, k, &Manhattan).unwrap())
+                    .collect();
+                assert_eq!(
+                    matrix_distancias(&target, &training, k, &Manhattan).unwrap(),
+                    secuencial
+                );
+            }
+        }
+    }
 }
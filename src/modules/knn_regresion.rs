@@ -0,0 +1,169 @@
+use super::dataset_struct::Dataset;
+use super::distancias::Distance;
+use super::error::MlError;
+use super::knn_ia::k_nearest_indices;
+use super::metricas::{regression_metrics, RegressionMetrics};
+
+/// How the neighbors' targets are combined into a prediction
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RegressionStrategy {
+    /// Arithmetic mean of the k targets
+    #[default]
+    Mean,
+    /// Median of the k targets, robust to outliers
+    Median,
+    /// Mean weighted by 1 / distance; neighbors at distance 0 win outright
+    WeightedMean,
+}
+
+/// KNN regressor: predicts a continuous value from the targets of the k nearest rows
+///
+/// Uses the same feature rows as the classifier in `knn_ia`, but each training
+/// row has a numeric target instead of (or as well as) a class label.
+#[derive(Debug, Clone)]
+pub struct KnnRegressor {
+    pub training_dataset: Dataset,
+    pub training_targets: Vec<f64>,
+    pub k: usize,
+    pub strategy: RegressionStrategy,
+}
+
+/// Predictions together with their error metrics
+#[derive(Debug, Clone)]
+pub struct RegressionReport {
+    pub predictions: Vec<f64>,
+    pub metrics: RegressionMetrics,
+}
+
+impl KnnRegressor {
+    /// # Returns
+    /// - Err(MlError::InvalidInput) if `k` is 0 or there is not one target per training row
+    /// - Err(MlError::EmptyDataset) if the training dataset has no rows
+    pub fn new(
+        training_dataset: Dataset,
+        training_targets: Vec<f64>,
+        k: usize,
+        strategy: RegressionStrategy,
+    ) -> Result<Self, MlError> {
+        if k == 0 {
+            return Err(MlError::InvalidInput("K debe ser mayor a 0".to_string()));
+        }
+        if training_dataset.rows.is_empty() {
+            return Err(MlError::EmptyDataset);
+        }
+        if training_dataset.rows.len() != training_targets.len() {
+            return Err(MlError::InvalidInput(format!(
+                "Training dataset has {} samples but {} targets",
+                training_dataset.rows.len(),
+                training_targets.len()
+            )));
+        }
+
+        Ok(KnnRegressor {
+            training_dataset,
+            training_targets,
+            k,
+            strategy,
+        })
+    }
+
+    /// Predicts a value for every row of `target_dataset`
+    pub fn predict(
+        &self,
+        target_dataset: &Dataset,
+        metrica: &dyn Distance,
+    ) -> Result<Vec<f64>, MlError> {
+        target_dataset
+            .rows
+            .iter()
+            .map(|target| {
+                let neighbors =
+                    k_nearest_indices(&self.training_dataset.rows, target, self.k, metrica)?;
+                Ok(predict_value(
+                    &neighbors,
+                    &self.training_targets,
+                    self.strategy,
+                ))
+            })
+            .collect()
+    }
+
+    /// Predicts `target_dataset` and reports MAE, RMSE and R² against `target_values`
+    pub fn evaluate(
+        &self,
+        target_dataset: &Dataset,
+        target_values: &[f64],
+        metrica: &dyn Distance,
+    ) -> Result<RegressionReport, MlError> {
+        if target_dataset.rows.len() != target_values.len() {
+            return Err(MlError::InvalidInput(format!(
+                "Target dataset has {} samples but {} target values",
+                target_dataset.rows.len(),
+                target_values.len()
+            )));
+        }
+
+        let predictions = self.predict(target_dataset, metrica)?;
+        let metrics = regression_metrics(target_values, &predictions)?;
+
+        Ok(RegressionReport {
+            predictions,
+            metrics,
+        })
+    }
+}
+
+/// Combines the targets of `neighbors` (training index, distance) into one prediction
+///
+/// Returns NaN if there are no neighbors.
+pub fn predict_value(
+    neighbors: &[(usize, f64)],
+    training_targets: &[f64],
+    strategy: RegressionStrategy,
+) -> f64 {
+    if neighbors.is_empty() {
+        return f64::NAN;
+    }
+
+    let targets: Vec<f64> = neighbors
+        .iter()
+        .map(|&(j, _)| training_targets[j])
+        .collect();
+
+    match strategy {
+        RegressionStrategy::Mean => targets.iter().sum::<f64>() / targets.len() as f64,
+        RegressionStrategy::Median => {
+            let mut sorted = targets;
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let mid = sorted.len() / 2;
+            if sorted.len().is_multiple_of(2) {
+                (sorted[mid - 1] + sorted[mid]) / 2.0
+            } else {
+                sorted[mid]
+            }
+        }
+        RegressionStrategy::WeightedMean => {
+            // Exact matches have infinite weight: average only those
+            let exact: Vec<f64> = neighbors
+                .iter()
+                .filter(|(_, d)| *d == 0.0)
+                .map(|&(j, _)| training_targets[j])
+                .collect();
+            if !exact.is_empty() {
+                return exact.iter().sum::<f64>() / exact.len() as f64;
+            }
+
+            let (sum, total_weight) = neighbors
+                .iter()
+                .map(|&(j, d)| (training_targets[j], 1.0 / d))
+                .filter(|(_, w)| w.is_finite())
+                .fold((0.0, 0.0), |(s, tw), (t, w)| (s + t * w, tw + w));
+
+            if total_weight > 0.0 {
+                sum / total_weight
+            } else {
+                f64::NAN
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::BTreeSet;
+
+use super::error::MlError;
+
+/// Matriz de confusion: filas = clase real, columnas = clase predicha
+#[derive(Debug, Clone)]
+pub struct ConfusionMatrix {
+    /// Clases en orden alfabetico, indexan filas y columnas de `matrix`
+    pub labels: Vec<String>,
+    pub matrix: Vec<Vec<usize>>,
+}
+
+/// Precision, recall, F1 y soporte de una clase
+#[derive(Debug, Clone)]
+pub struct ClassMetrics {
+    pub label: String,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    /// Numero de muestras cuya clase real es `label`
+    pub support: usize,
+}
+
+/// Promedio de precision, recall y F1 sobre todas las clases
+#[derive(Debug, Clone)]
+pub struct AveragedMetrics {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+/// Reporte completo de clasificacion
+#[derive(Debug, Clone)]
+pub struct ClassificationReport {
+    pub confusion_matrix: ConfusionMatrix,
+    pub per_class: Vec<ClassMetrics>,
+    pub accuracy: f64,
+    /// Promedio simple: cada clase pesa lo mismo
+    pub macro_avg: AveragedMetrics,
+    /// Promedio ponderado por `support` de cada clase
+    pub weighted_avg: AveragedMetrics,
+}
+
+/// Metricas de error para regresion
+#[derive(Debug, Clone)]
+pub struct RegressionMetrics {
+    /// Mean absolute error
+    pub mae: f64,
+    /// Root mean squared error
+    pub rmse: f64,
+    /// Coeficiente de determinacion, 1.0 es un ajuste perfecto
+    pub r2: f64,
+}
+
+/// Construye la matriz de confusion a partir de etiquetas reales y predichas
+///
+/// Funciona con las predicciones de `knn_ia::evaluate_knn` y de `ID3::predict`.
+/// Las clases son la union de ambas listas, en orden alfabetico.
+///
+/// # Returns
+/// - Err(MlError::InvalidInput) si las listas no tienen el mismo tamaño
+pub fn confusion_matrix<T: AsRef<str>>(
+    y_true: &[T],
+    y_pred: &[T],
+) -> Result<ConfusionMatrix, MlError> {
+    if y_true.len() != y_pred.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Hay {} etiquetas reales pero {} predicciones",
+            y_true.len(),
+            y_pred.len()
+        )));
+    }
+
+    let labels: Vec<String> = y_true
+        .iter()
+        .chain(y_pred.iter())
+        .map(|l| l.as_ref())
+        .collect::<BTreeSet<&str>>()
+        .into_iter()
+        .map(|l| l.to_string())
+        .collect();
+
+    let index_of = |label: &str| {
+        labels
+            .binary_search_by(|l| l.as_str().cmp(label))
+            .unwrap_or(0)
+    };
+
+    let mut matrix = vec![vec![0; labels.len()]; labels.len()];
+    for (t, p) in y_true.iter().zip(y_pred.iter()) {
+        matrix[index_of(t.as_ref())][index_of(p.as_ref())] += 1;
+    }
+
+    Ok(ConfusionMatrix { labels, matrix })
+}
+
+/// Calcula precision, recall, F1 y soporte por clase, con promedios macro y ponderado
+///
+/// # Notes
+/// - Si una clase nunca se predice su precision es 0.0 (y lo mismo para recall
+///   si no aparece en `y_true`), en lugar de dividir entre cero
+pub fn classification_report<T: AsRef<str>>(
+    y_true: &[T],
+    y_pred: &[T],
+) -> Result<ClassificationReport, MlError> {
+    let cm = confusion_matrix(y_true, y_pred)?;
+    let n_classes = cm.labels.len();
+    let total = y_true.len();
+
+    let mut per_class = Vec::with_capacity(n_classes);
+    for (i, label) in cm.labels.iter().enumerate() {
+        let tp = cm.matrix[i][i];
+        let predicted: usize = (0..n_classes).map(|r| cm.matrix[r][i]).sum();
+        let support: usize = cm.matrix[i].iter().sum();
+
+        let precision = ratio(tp, predicted);
+        let recall = ratio(tp, support);
+        let f1 = if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        };
+
+        per_class.push(ClassMetrics {
+            label: label.clone(),
+            precision,
+            recall,
+            f1,
+            support,
+        });
+    }
+
+    let correct: usize = (0..n_classes).map(|i| cm.matrix[i][i]).sum();
+    let accuracy = ratio(correct, total);
+
+    let macro_avg = promediar(&per_class, |_| 1.0);
+    let weighted_avg = promediar(&per_class, |m| m.support as f64);
+
+    Ok(ClassificationReport {
+        confusion_matrix: cm,
+        per_class,
+        accuracy,
+        macro_avg,
+        weighted_avg,
+    })
+}
+
+/// Calcula MAE, RMSE y R² entre valores reales y predichos
+///
+/// # Notes
+/// - Si `y_true` es constante R² no esta definido: se regresa 1.0 si la
+///   prediccion es perfecta y 0.0 en otro caso
+pub fn regression_metrics(y_true: &[f64], y_pred: &[f64]) -> Result<RegressionMetrics, MlError> {
+    if y_true.len() != y_pred.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Hay {} valores reales pero {} predicciones",
+            y_true.len(),
+            y_pred.len()
+        )));
+    }
+    if y_true.is_empty() {
+        return Err(MlError::EmptyDataset);
+    }
+
+    let n = y_true.len() as f64;
+    let mean = y_true.iter().sum::<f64>() / n;
+
+    let mut abs_error = 0.0;
+    let mut ss_res = 0.0;
+    let mut ss_tot = 0.0;
+    for (t, p) in y_true.iter().zip(y_pred.iter()) {
+        abs_error += (t - p).abs();
+        ss_res += (t - p).powi(2);
+        ss_tot += (t - mean).powi(2);
+    }
+
+    let r2 = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else if ss_res == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+
+    Ok(RegressionMetrics {
+        mae: abs_error / n,
+        rmse: (ss_res / n).sqrt(),
+        r2,
+    })
+}
+
+impl ConfusionMatrix {
+    pub fn display(&self) {
+        println!("Confusion matrix (filas = real, columnas = predicha):");
+        println!("{:>12} {:?}", "", self.labels);
+        for (label, row) in self.labels.iter().zip(self.matrix.iter()) {
+            println!("{:>12} {:?}", label, row);
+        }
+    }
+}
+
+impl ClassificationReport {
+    pub fn display(&self) {
+        println!(
+            "{:>12} {:>10} {:>10} {:>10} {:>10}",
+            "", "precision", "recall", "f1", "support"
+        );
+        for m in &self.per_class {
+            println!(
+                "{:>12} {:>10.4} {:>10.4} {:>10.4} {:>10}",
+                m.label, m.precision, m.recall, m.f1, m.support
+            );
+        }
+        let total: usize = self.per_class.iter().map(|m| m.support).sum();
+        println!("{:>12} {:>32.4} {:>10}", "accuracy", self.accuracy, total);
+        for (name, avg) in [
+            ("macro avg", &self.macro_avg),
+            ("weighted avg", &self.weighted_avg),
+        ] {
+            println!(
+                "{:>12} {:>10.4} {:>10.4} {:>10.4} {:>10}",
+                name, avg.precision, avg.recall, avg.f1, total
+            );
+        }
+    }
+}
+
+fn ratio(num: usize, den: usize) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
+
+fn promediar<W: Fn(&ClassMetrics) -> f64>(per_class: &[ClassMetrics], peso: W) -> AveragedMetrics {
+    let total_peso: f64 = per_class.iter().map(&peso).sum();
+    if total_peso == 0.0 {
+        return AveragedMetrics {
+            precision: 0.0,
+            recall: 0.0,
+            f1: 0.0,
+        };
+    }
+    let media = |f: fn(&ClassMetrics) -> f64| {
+        per_class.iter().map(|m| f(m) * peso(m)).sum::<f64>() / total_peso
+    };
+    AveragedMetrics {
+        precision: media(|m| m.precision),
+        recall: media(|m| m.recall),
+        f1: media(|m| m.f1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cerca(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    // 6 A, 2 B y 2 C; C nunca se predice
+    fn ejemplo() -> (Vec<&'static str>, Vec<&'static str>) {
+        (
+            vec!["A", "A", "A", "A", "A", "A", "B", "B", "C", "C"],
+            vec!["A", "A", "A", "A", "A", "B", "A", "B", "A", "A"],
+        )
+    }
+
+    #[test]
+    fn confusion_matrix_cuenta_por_clase_real_y_predicha() {
+        let (y_true, y_pred) = ejemplo();
+        let cm = confusion_matrix(&y_true, &y_pred).unwrap();
+
+        assert_eq!(cm.labels, vec!["A", "B", "C"]);
+        assert_eq!(cm.matrix, vec![vec![5, 1, 0], vec![1, 1, 0], vec![2, 0, 0]]);
+
+        assert!(matches!(
+            confusion_matrix(&["A", "B"], &["A"]),
+            Err(MlError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn classification_report_calculado_a_mano() {
+        let (y_true, y_pred) = ejemplo();
+        let report = classification_report(&y_true, &y_pred).unwrap();
+
+        // A: tp 5 de 8 predichas y 6 reales
+        let a = &report.per_class[0];
+        assert!(cerca(a.precision, 5.0 / 8.0));
+        assert!(cerca(a.recall, 5.0 / 6.0));
+        assert!(cerca(a.f1, 5.0 / 7.0));
+        assert_eq!(a.support, 6);
+
+        let b = &report.per_class[1];
+        assert!(cerca(b.precision, 0.5) && cerca(b.recall, 0.5) && cerca(b.f1, 0.5));
+        assert_eq!(b.support, 2);
+
+        // C nunca se predice: todo 0.0 en lugar de NaN
+        let c = &report.per_class[2];
+        assert_eq!((c.precision, c.recall, c.f1, c.support), (0.0, 0.0, 0.0, 2));
+
+        assert!(cerca(report.accuracy, 0.6));
+
+        // Macro: cada clase pesa 1/3
+        assert!(cerca(report.macro_avg.precision, (5.0 / 8.0 + 0.5) / 3.0));
+        assert!(cerca(report.macro_avg.recall, (5.0 / 6.0 + 0.5) / 3.0));
+        assert!(cerca(report.macro_avg.f1, (5.0 / 7.0 + 0.5) / 3.0));
+
+        // Ponderado por soporte 6 / 2 / 2: la clase grande domina
+        assert!(cerca(
+            report.weighted_avg.precision,
+            (6.0 * 5.0 / 8.0 + 2.0 * 0.5) / 10.0
+        ));
+        assert!(cerca(report.weighted_avg.recall, 0.6));
+        assert!(cerca(
+            report.weighted_avg.f1,
+            (6.0 * 5.0 / 7.0 + 2.0 * 0.5) / 10.0
+        ));
+    }
+
+    #[test]
+    fn classification_report_con_clase_solo_predicha() {
+        let report = classification_report(&["A", "A"], &["A", "D"]).unwrap();
+        let d = &report.per_class[1];
+
+        assert_eq!(d.label, "D");
+        assert_eq!((d.precision, d.recall, d.f1, d.support), (0.0, 0.0, 0.0, 0));
+        // Soporte 0: no cuenta en el ponderado pero si en el macro
+        assert!(cerca(report.weighted_avg.recall, 0.5));
+        assert!(cerca(report.macro_avg.recall, 0.25));
+    }
+}
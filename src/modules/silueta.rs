@@ -0,0 +1,89 @@
+use super::distancias::euclidean_distance;
+use super::error::MlError;
+
+/// Calcula el coeficiente de silueta de cada punto
+///
+/// # Algorithm Overview:
+/// For every point i:
+/// - a(i): mean distance from i to the other points in its own cluster
+/// - b(i): lowest mean distance from i to the points of any other cluster
+/// - s(i) = (b(i) - a(i)) / max(a(i), b(i))
+///
+/// # Parameters:
+/// - `data`: &[Vec<f64>] - The dataset that was clustered
+/// - `clusters`: &[usize] - Cluster index for each point, e.g. the output of `asignar_a_clusters`
+///
+/// # Returns:
+/// - Ok(Vec<f64>) - One coefficient in [-1, 1] per point, in the same order as `data`
+/// - Err(MlError) - If `data` and `clusters` differ in length or the rows have different sizes
+///
+/// # Notes:
+/// - Points in a single-point cluster get 0.0, as in the usual definition
+/// - If there is only one cluster, every coefficient is 0.0
+/// - Cost is O(n²) distance computations
+pub fn coeficientes_silueta(data: &[Vec<f64>], clusters: &[usize]) -> Result<Vec<f64>, MlError> {
+    if data.len() != clusters.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Dataset tiene {} muestras pero {} asignaciones de cluster",
+            data.len(),
+            clusters.len()
+        )));
+    }
+
+    let k = clusters.iter().map(|&c| c + 1).max().unwrap_or(0);
+    let mut tamanos = vec![0usize; k];
+    for &c in clusters {
+        tamanos[c] += 1;
+    }
+
+    let coeficientes = data
+        .iter()
+        .zip(clusters.iter())
+        .enumerate()
+        .map(|(i, (point, &propio))| {
+            if tamanos[propio] <= 1 {
+                return Ok(0.0);
+            }
+
+            // Suma de distancias de este punto a cada cluster
+            let mut sumas = vec![0.0; k];
+            for (j, (other, &c)) in data.iter().zip(clusters.iter()).enumerate() {
+                if i != j {
+                    sumas[c] += euclidean_distance(point, other)?;
+                }
+            }
+
+            let a = sumas[propio] / (tamanos[propio] - 1) as f64;
+            let b = (0..k)
+                .filter(|&c| c != propio && tamanos[c] > 0)
+                .map(|c| sumas[c] / tamanos[c] as f64)
+                .fold(f64::INFINITY, f64::min);
+
+            if !b.is_finite() {
+                // Solo existe un cluster con puntos
+                return Ok(0.0);
+            }
+
+            let denominador = a.max(b);
+            if denominador > 0.0 {
+                Ok((b - a) / denominador)
+            } else {
+                Ok(0.0)
+            }
+        })
+        .collect::<Result<Vec<f64>, MlError>>()?;
+
+    Ok(coeficientes)
+}
+
+/// Coeficiente de silueta promedio de toda la agrupacion
+///
+/// Valores cercanos a 1 indican clusters compactos y bien separados,
+/// cercanos a 0 clusters traslapados y negativos puntos mal asignados
+pub fn silueta_promedio(data: &[Vec<f64>], clusters: &[usize]) -> Result<f64, MlError> {
+    let coeficientes = coeficientes_silueta(data, clusters)?;
+    if coeficientes.is_empty() {
+        return Ok(0.0);
+    }
+    Ok(coeficientes.iter().sum::<f64>() / coeficientes.len() as f64)
+}
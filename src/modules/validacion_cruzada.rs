@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+
+use super::create_dataset::crear_rng;
+use super::dataset_struct::Dataset;
+use super::error::MlError;
+
+/// Resultado de la validacion cruzada
+#[derive(Debug, Clone)]
+pub struct ResultadoValidacion {
+    /// Score (p. ej. accuracy) obtenido en cada fold
+    pub fold_scores: Vec<f64>,
+    /// Promedio de `fold_scores`
+    pub mean: f64,
+    /// Desviacion estandar (poblacional) de `fold_scores`
+    pub std_dev: f64,
+}
+
+/// Divide los indices `0..n` en `n_folds` grupos de testing de tamaño similar
+///
+/// # Returns
+/// - Ok(Vec<Vec<usize>>) - Los indices de testing de cada fold
+/// - Err(MlError::InvalidInput) - Si `n_folds < 2` o hay menos muestras que folds
+pub fn k_fold_indices(
+    n: usize,
+    n_folds: usize,
+    random_seed: Option<u64>,
+) -> Result<Vec<Vec<usize>>, MlError> {
+    validar_folds(n, n_folds)?;
+
+    let mut rng = crear_rng(random_seed);
+    let mut indices: Vec<usize> = (0..n).collect();
+    indices.shuffle(&mut rng);
+
+    let mut folds = vec![Vec::new(); n_folds];
+    for (i, idx) in indices.into_iter().enumerate() {
+        folds[i % n_folds].push(idx);
+    }
+
+    Ok(folds)
+}
+
+/// Igual que `k_fold_indices` pero cada fold conserva la proporcion de clases
+///
+/// Los indices de cada clase se mezclan y se reparten de forma circular entre
+/// los folds, asi una clase minoritaria aparece en tantos folds como sea posible
+pub fn stratified_k_fold_indices(
+    labels: &[String],
+    n_folds: usize,
+    random_seed: Option<u64>,
+) -> Result<Vec<Vec<usize>>, MlError> {
+    validar_folds(labels.len(), n_folds)?;
+
+    let mut rng = crear_rng(random_seed);
+
+    // BTreeMap para que el reparto sea determinista con la misma semilla
+    let mut por_clase: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        por_clase.entry(label.as_str()).or_default().push(i);
+    }
+
+    let mut folds = vec![Vec::new(); n_folds];
+    let mut siguiente = 0;
+    for indices in por_clase.values_mut() {
+        indices.shuffle(&mut rng);
+        for &idx in indices.iter() {
+            folds[siguiente % n_folds].push(idx);
+            siguiente += 1;
+        }
+    }
+
+    Ok(folds)
+}
+
+/// Ejecuta validacion cruzada K-fold sobre un dataset
+///
+/// # Parametros
+/// - `dataset`: Dataset completo, cada fold se usa una vez como testing
+/// - `n_folds`: Numero de folds (K)
+/// - `stratify`: Si es `true` usa `stratified_k_fold_indices` (requiere `class_labels`)
+/// - `random_seed`: Semilla opcional para que los folds sean reproducibles
+/// - `model`: Closure que recibe (training, testing) y regresa un score, p. ej.
+///   `|train, test| evaluate_knn(test, train, 5, &Euclidean, VotingStrategy::Uniform, Verbosity::Silent).map(|r| r.accuracy)`
+///
+/// # Returns
+/// - Ok(ResultadoValidacion) - Score de cada fold, promedio y desviacion estandar
+/// - Err(MlError) - Si los parametros son invalidos o el modelo falla en algun fold
+pub fn cross_validate<F>(
+    dataset: &Dataset,
+    n_folds: usize,
+    stratify: bool,
+    random_seed: Option<u64>,
+    mut model: F,
+) -> Result<ResultadoValidacion, MlError>
+where
+    F: FnMut(&Dataset, &Dataset) -> Result<f64, MlError>,
+{
+    if !dataset.class_labels.is_empty() && dataset.class_labels.len() != dataset.rows.len() {
+        return Err(MlError::InvalidInput(format!(
+            "Dataset tiene {} muestras pero {} class labels",
+            dataset.rows.len(),
+            dataset.class_labels.len()
+        )));
+    }
+
+    let folds = if stratify {
+        if dataset.class_labels.is_empty() {
+            return Err(MlError::InvalidInput(
+                "No se puede estratificar un dataset sin class labels".to_string(),
+            ));
+        }
+        stratified_k_fold_indices(&dataset.class_labels, n_folds, random_seed)?
+    } else {
+        k_fold_indices(dataset.rows.len(), n_folds, random_seed)?
+    };
+
+    let mut fold_scores = Vec::with_capacity(n_folds);
+    for (f, test_idx) in folds.iter().enumerate() {
+        let train_idx: Vec<usize> = folds
+            .iter()
+            .enumerate()
+            .filter(|(g, _)| *g != f)
+            .flat_map(|(_, fold)| fold.iter().copied())
+            .collect();
+
+        let training = dataset.subset(&train_idx);
+        let testing = dataset.subset(test_idx);
+        fold_scores.push(model(&training, &testing)?);
+    }
+
+    let mean = fold_scores.iter().sum::<f64>() / fold_scores.len() as f64;
+    let std_dev = (fold_scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>()
+        / fold_scores.len() as f64)
+        .sqrt();
+
+    Ok(ResultadoValidacion {
+        fold_scores,
+        mean,
+        std_dev,
+    })
+}
+
+fn validar_folds(n: usize, n_folds: usize) -> Result<(), MlError> {
+    if n_folds < 2 {
+        return Err(MlError::InvalidInput(format!(
+            "Se necesitan al menos 2 folds, se recibio {}",
+            n_folds
+        )));
+    }
+    if n < n_folds {
+        return Err(MlError::InvalidInput(format!(
+            "No se pueden hacer {} folds con {} muestras",
+            n_folds, n
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+/// Nivel de mensajes que imprimen KNN y K-means mientras trabajan
+///
+/// Por defecto no se imprime nada; los resultados se regresan en structs y
+/// quien llama decide que mostrar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    #[default]
+    Silent,
+    /// Imprime progreso (iteraciones, avisos, vecino mas cercano por muestra)
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn is_verbose(&self) -> bool {
+        *self == Verbosity::Verbose
+    }
+}